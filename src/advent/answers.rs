@@ -0,0 +1,258 @@
+//! Expected puzzle answers, read from a local `answers.toml`.
+//!
+//! Only the subset of TOML needed for the file is supported: an array of `[[answer]]`
+//! tables with integer and basic string values, and `#` comments.
+//!
+//! ```toml
+//! [[answer]]
+//! day = 15
+//! part = 2
+//! input = "data/day15.txt"
+//! expected = "315"
+//! ```
+
+use std::path::PathBuf;
+
+use anyhow::Result;
+
+use super::runner::{Outcome, Part};
+use super::Answer;
+
+/// Expected is an expected answer for one part of a day, given an input file.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Expected {
+    pub day: u8,
+    pub part: Part,
+    pub input: PathBuf,
+    pub expected: String,
+}
+
+impl Expected {
+    /// matches returns true if the answer is the expected one.
+    /// Trailing whitespace is ignored on every line, so rendered answers can be
+    /// written without padding.
+    pub fn matches(&self, answer: &Answer) -> bool {
+        let normalize = |s: &str| {
+            s.trim_end()
+                .lines()
+                .map(str::trim_end)
+                .collect::<Vec<_>>()
+                .join("\n")
+        };
+        normalize(&answer.to_string()) == normalize(&self.expected)
+    }
+}
+
+/// Verdict is the result of checking a day's part against its expected answer.
+#[derive(Debug, PartialEq)]
+pub enum Verdict {
+    Pass,
+    /// Fail holds the reason the part failed.
+    Fail(String),
+}
+
+/// check compares the outcome of running a part to the expected answer.
+pub fn check(expected: &Expected, outcome: &Outcome) -> Verdict {
+    match &outcome.answer {
+        Ok(answer) if expected.matches(answer) => Verdict::Pass,
+        Ok(answer) => Verdict::Fail(format!("expected {}, got {}", expected.expected, answer)),
+        Err(err) => Verdict::Fail(format!("{:#}", err)),
+    }
+}
+
+pub fn read_answers(mut input: impl std::io::Read) -> Result<Vec<Expected>> {
+    let mut buf = String::new();
+    input.read_to_string(&mut buf)?;
+    parse_answers(&buf)
+}
+
+pub fn parse_answers(s: &str) -> Result<Vec<Expected>> {
+    let mut answers = vec![];
+    let mut current: Option<Fields> = None;
+
+    for (i, line) in s.lines().enumerate() {
+        let line_number = i + 1;
+        let context = || format!("answers line {}", line_number);
+        let line = strip_comment(line).trim();
+        if line.is_empty() {
+            continue;
+        }
+        if line == "[[answer]]" {
+            if let Some(fields) = current.take() {
+                answers.push(fields.build()?);
+            }
+            current = Some(Fields::new(line_number));
+            continue;
+        }
+
+        let fields = current
+            .as_mut()
+            .ok_or_else(|| anyhow::Error::msg("expected [[answer]]"))
+            .map_err(|err| err.context(context()))?;
+        let (key, value) = line
+            .split_once('=')
+            .ok_or_else(|| anyhow::format_err!("expected key = value"))
+            .map_err(|err| err.context(context()))?;
+        let value = Value::parse(value.trim()).map_err(|err| err.context(context()))?;
+        fields
+            .set(key.trim(), value)
+            .map_err(|err| err.context(context()))?;
+    }
+    if let Some(fields) = current.take() {
+        answers.push(fields.build()?);
+    }
+
+    Ok(answers)
+}
+
+/// strip_comment removes a trailing `#` comment, unless the `#` is in a string.
+fn strip_comment(line: &str) -> &str {
+    let mut in_string = false;
+    let mut escaped = false;
+    for (i, c) in line.char_indices() {
+        match c {
+            '\\' if in_string => {
+                escaped = !escaped;
+                continue;
+            }
+            '"' if !escaped => in_string = !in_string,
+            '#' if !in_string => return &line[..i],
+            _ => (),
+        }
+        escaped = false;
+    }
+    line
+}
+
+enum Value {
+    Integer(i128),
+    String(String),
+}
+
+impl Value {
+    fn parse(s: &str) -> Result<Self> {
+        if let Some(quoted) = s.strip_prefix('"') {
+            let quoted = quoted
+                .strip_suffix('"')
+                .ok_or_else(|| anyhow::Error::msg("unterminated string"))?;
+            return Ok(Value::String(unescape(quoted)?));
+        }
+        s.replace('_', "")
+            .parse()
+            .map(Value::Integer)
+            .map_err(|_| anyhow::format_err!("bad value '{}'", s))
+    }
+}
+
+fn unescape(s: &str) -> Result<String> {
+    let mut out = String::with_capacity(s.len());
+    let mut chars = s.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            out.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('n') => out.push('\n'),
+            Some('t') => out.push('\t'),
+            Some('"') => out.push('"'),
+            Some('\\') => out.push('\\'),
+            c => return Err(anyhow::format_err!("bad escape '\\{}'", c.unwrap_or(' '))),
+        }
+    }
+    Ok(out)
+}
+
+/// Fields collects the keys of a single `[[answer]]` table.
+struct Fields {
+    line_number: usize,
+    day: Option<u8>,
+    part: Option<Part>,
+    input: Option<PathBuf>,
+    expected: Option<String>,
+}
+
+impl Fields {
+    fn new(line_number: usize) -> Self {
+        Self {
+            line_number,
+            day: None,
+            part: None,
+            input: None,
+            expected: None,
+        }
+    }
+
+    fn set(&mut self, key: &str, value: Value) -> Result<()> {
+        match (key, value) {
+            ("day", Value::Integer(day)) => {
+                self.day = Some(
+                    day.try_into()
+                        .map_err(|_| anyhow::format_err!("bad day {}", day))?,
+                )
+            }
+            ("part", Value::Integer(part)) => self.part = Some(part.to_string().parse()?),
+            ("input", Value::String(input)) => self.input = Some(PathBuf::from(input)),
+            ("expected", Value::String(expected)) => self.expected = Some(expected),
+            ("expected", Value::Integer(expected)) => self.expected = Some(expected.to_string()),
+            ("day" | "part" | "input", _) => {
+                return Err(anyhow::format_err!("bad type for '{}'", key))
+            }
+            _ => return Err(anyhow::format_err!("unknown key '{}'", key)),
+        }
+        Ok(())
+    }
+
+    fn build(self) -> Result<Expected> {
+        let missing =
+            |key| anyhow::format_err!("answer at line {} is missing '{}'", self.line_number, key);
+        Ok(Expected {
+            day: self.day.ok_or_else(|| missing("day"))?,
+            part: self.part.ok_or_else(|| missing("part"))?,
+            input: self.input.clone().ok_or_else(|| missing("input"))?,
+            expected: self.expected.clone().ok_or_else(|| missing("expected"))?,
+        })
+    }
+}
+
+#[test]
+fn test_parse_answers() {
+    let s = r###"
+# checked against the examples
+[[answer]]
+day = 1
+part = 1
+input = "data/day1.txt" # trailing comment
+expected = 7
+
+[[answer]]
+day = 13
+part = 2
+input = "data/#13.txt"
+expected = "##\n #"
+"###;
+    let answers = parse_answers(s).unwrap();
+    assert_eq!(
+        answers,
+        vec![
+            Expected {
+                day: 1,
+                part: Part::One,
+                input: PathBuf::from("data/day1.txt"),
+                expected: "7".to_owned(),
+            },
+            Expected {
+                day: 13,
+                part: Part::Two,
+                input: PathBuf::from("data/#13.txt"),
+                expected: "##\n #".to_owned(),
+            },
+        ]
+    );
+    assert!(answers[1].matches(&Answer::Text("##  \n #\n".to_owned())));
+    assert!(!answers[0].matches(&Answer::Number(8)));
+
+    assert!(parse_answers("day = 1").is_err());
+    assert!(parse_answers("[[answer]]\nday = 1\npart = 1\ninput = \"x\"").is_err());
+    assert!(parse_answers("[[answer]]\nday = \"1\"").is_err());
+}
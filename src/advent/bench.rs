@@ -0,0 +1,187 @@
+use std::fmt::Display;
+use std::io::{Read, Write};
+use std::time::{Duration, Instant};
+
+use anyhow::Result;
+
+use super::runner::{Input, Part};
+use super::{solution, DynSolution};
+
+/// Phase is a timed step of solving a day.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Phase {
+    Parse,
+    Solve(Part),
+}
+
+impl Display for Phase {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Phase::Parse => f.write_str("parse"),
+            Phase::Solve(part) => write!(f, "part{}", part),
+        }
+    }
+}
+
+/// Stats summarizes the durations of repeated runs.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Stats {
+    pub runs: usize,
+    pub min: Duration,
+    pub median: Duration,
+    pub max: Duration,
+}
+
+impl Stats {
+    /// from_samples returns None if there are no samples.
+    pub fn from_samples(mut samples: Vec<Duration>) -> Option<Self> {
+        samples.sort_unstable();
+        let mid = samples.len() / 2;
+        let median = match samples.len() % 2 {
+            0 => (*samples.get(mid.checked_sub(1)?)? + samples[mid]) / 2,
+            _ => samples[mid],
+        };
+        Some(Self {
+            runs: samples.len(),
+            min: *samples.first()?,
+            median,
+            max: *samples.last()?,
+        })
+    }
+}
+
+/// Timing is the result of benchmarking one phase of a day.
+#[derive(Debug)]
+pub struct Timing {
+    pub day: u8,
+    pub phase: Phase,
+    pub stats: Result<Stats>,
+}
+
+/// bench times parsing and solving of a day `runs` times each.
+///
+/// The input is kept in memory so reading it isn't part of the measurement. Parts are
+/// solved using the input from the last parse run.
+pub fn bench(solution: &dyn DynSolution, input: &[u8], runs: usize) -> Vec<Timing> {
+    let day = solution.day();
+    let timing = |phase, stats| Timing { day, phase, stats };
+    let runs = runs.max(1);
+
+    let mut samples = Vec::with_capacity(runs);
+    let mut parsed = None;
+    for _ in 0..runs {
+        let start = Instant::now();
+        let result = solution.parse(&mut &input[..]);
+        samples.push(start.elapsed());
+        match result {
+            Ok(p) => parsed = Some(p),
+            Err(err) => {
+                return std::iter::once(Phase::Parse)
+                    .chain(Part::ALL.iter().map(|&part| Phase::Solve(part)))
+                    .map(|phase| {
+                        timing(
+                            phase,
+                            Err(anyhow::format_err!("failed to parse input: {:#}", err)),
+                        )
+                    })
+                    .collect();
+            }
+        }
+    }
+    let parsed = parsed.expect("at least one run");
+    let mut timings = vec![timing(Phase::Parse, summarize(samples))];
+
+    for part in Part::ALL {
+        let mut samples = Vec::with_capacity(runs);
+        let mut failure = None;
+        for _ in 0..runs {
+            let start = Instant::now();
+            let result = match part {
+                Part::One => solution.part1(parsed.as_ref()),
+                Part::Two => solution.part2(parsed.as_ref()),
+            };
+            samples.push(start.elapsed());
+            if let Err(err) = result {
+                failure = Some(err);
+                break;
+            }
+        }
+        let stats = match failure {
+            Some(err) => Err(err),
+            None => summarize(samples),
+        };
+        timings.push(timing(Phase::Solve(part), stats));
+    }
+
+    timings
+}
+
+/// bench_day benchmarks a registered day, reading the input from `input` or the day's
+/// default input.
+pub fn bench_day(day: u8, input: Option<&Input>, runs: usize) -> Result<Vec<Timing>> {
+    let solution = solution(day).ok_or_else(|| anyhow::format_err!("day {} is not solved", day))?;
+
+    let mut buf = vec![];
+    input
+        .cloned()
+        .unwrap_or_else(|| Input::default_for(day))
+        .open()?
+        .read_to_end(&mut buf)?;
+
+    Ok(bench(solution, &buf, runs))
+}
+
+fn summarize(samples: Vec<Duration>) -> Result<Stats> {
+    Stats::from_samples(samples).ok_or_else(|| anyhow::Error::msg("no runs"))
+}
+
+/// write_csv writes the timings as CSV, with durations in milliseconds.
+/// Failed phases have empty durations and the error in the last column.
+pub fn write_csv(mut w: impl Write, timings: &[Timing]) -> Result<()> {
+    writeln!(w, "day,phase,runs,min_ms,median_ms,max_ms,error")?;
+    for t in timings {
+        match &t.stats {
+            Ok(stats) => writeln!(
+                w,
+                "{},{},{},{:.4},{:.4},{:.4},",
+                t.day,
+                t.phase,
+                stats.runs,
+                millis(stats.min),
+                millis(stats.median),
+                millis(stats.max)
+            )?,
+            Err(err) => writeln!(
+                w,
+                "{},{},0,,,,\"{}\"",
+                t.day,
+                t.phase,
+                format!("{:#}", err).replace('"', "\"\"")
+            )?,
+        }
+    }
+    Ok(())
+}
+
+pub fn millis(d: Duration) -> f64 {
+    d.as_secs_f64() * 1000.0
+}
+
+#[test]
+fn test_stats() {
+    let ms = Duration::from_millis;
+    assert_eq!(Stats::from_samples(vec![]), None);
+    assert_eq!(
+        Stats::from_samples(vec![ms(5), ms(1), ms(3)]),
+        Some(Stats {
+            runs: 3,
+            min: ms(1),
+            median: ms(3),
+            max: ms(5)
+        })
+    );
+    assert_eq!(
+        Stats::from_samples(vec![ms(4), ms(1), ms(2), ms(8)]).map(|s| s.median),
+        Some(ms(3))
+    );
+}
@@ -1,30 +1,38 @@
 use anyhow::Result;
 
-use super::input;
+use super::{input, Answer, Solution};
 
-pub fn print_depth_increases(input: impl std::io::Read) -> Result<()> {
-    let (values, _): (Vec<i64>, usize) = input::get_input_numbers(input)?;
+/// Solver solves the day 1 puzzle.
+pub struct Solver;
 
-    println!(
-        "Depth increases: {}",
-        get_depth_increases(values.clone().into_iter())
-    );
+impl Solution for Solver {
+    const DAY: u8 = 1;
+    const TITLE: &'static str = "Sonar Sweep";
 
-    println!(
-        "Windowed depth increases: {}",
-        get_depth_increases(summed_windows(values.as_slice()))
-    );
-    Ok(())
+    type Input = Vec<i64>;
+
+    fn parse(&self, input: impl std::io::Read) -> Result<Self::Input> {
+        let (values, _) = input::get_input_numbers(input)?;
+        Ok(values)
+    }
+
+    fn part1(&self, values: &Self::Input) -> Result<Answer> {
+        Ok(get_depth_increases(values.iter().copied()).into())
+    }
+
+    fn part2(&self, values: &Self::Input) -> Result<Answer> {
+        Ok(get_depth_increases(summed_windows(values.as_slice())).into())
+    }
 }
 
-/// get_depth_increases returns number of depth increases in the input file for day1 found in input path.
+/// get_depth_increases returns the number of times a depth is larger than the previous one.
 pub fn get_depth_increases(v: impl Iterator<Item = i64>) -> i64 {
     let mut current_depth: Option<i64> = None;
 
     v.fold(0i64, |mut t, c| -> i64 {
         if let Some(depth) = current_depth {
             if depth < c {
-                t = t + 1;
+                t += 1;
             }
         }
         current_depth = Some(c);
@@ -32,7 +40,8 @@ pub fn get_depth_increases(v: impl Iterator<Item = i64>) -> i64 {
     })
 }
 
-pub fn summed_windows<'a>(v: &'a [i64]) -> impl Iterator<Item = i64> + 'a {
+/// summed_windows returns the sums of every three-measurement sliding window.
+pub fn summed_windows(v: &[i64]) -> impl Iterator<Item = i64> + '_ {
     v.windows(3).map(|x| x.iter().sum())
 }
 
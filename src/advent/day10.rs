@@ -1,28 +1,71 @@
 use anyhow::Result;
 
-use super::input;
+use super::{input, Answer, Solution};
 
-pub fn print_result(input: impl std::io::Read) -> Result<()> {
-    let input_lines = input::get_input_lines(input)?;
+/// Solver solves the day 10 puzzle.
+pub struct Solver;
 
+impl Solution for Solver {
+    const DAY: u8 = 10;
+    const TITLE: &'static str = "Syntax Scoring";
+
+    type Input = Vec<String>;
+
+    fn parse(&self, input: impl std::io::Read) -> Result<Self::Input> {
+        let lines = input::get_input_lines(input)?;
+        for (i, line) in lines.iter().enumerate() {
+            input::check_chars(i + 1, line, |c| "([{<>}])".contains(c), "not a chunk char")?;
+        }
+        Ok(lines)
+    }
+
+    fn part1(&self, input_lines: &Self::Input) -> Result<Answer> {
+        let (illegals, _) = check_lines(input_lines);
+
+        let sum: u64 = illegals
+            .iter()
+            .map(|&c| match c {
+                ')' => 3,
+                ']' => 57,
+                '}' => 1197,
+                '>' => 25137,
+                _ => panic!("unexpected illegal {}", c),
+            })
+            .sum();
+        Ok(sum.into())
+    }
+
+    fn part2(&self, input_lines: &Self::Input) -> Result<Answer> {
+        let (_, mut high_scores) = check_lines(input_lines);
+
+        high_scores.sort_unstable();
+        let mid_score = high_scores
+            .get(high_scores.len() / 2)
+            .ok_or_else(|| anyhow::Error::msg("no incomplete lines"))?;
+        Ok((*mid_score).into())
+    }
+}
+
+/// check_lines returns the first illegal character of every corrupted line, and the
+/// autocomplete score of every incomplete line.
+pub fn check_lines(input_lines: &[String]) -> (Vec<char>, Vec<i64>) {
     let mut high_scores: Vec<i64> = vec![];
     let mut illegals: Vec<char> = vec![];
 
     'outer: for line in input_lines {
         let mut stack = ChunkStack::new();
         for c in line.chars() {
-            if let Err(_) = stack.push(c) {
+            if stack.push(c).is_err() {
                 illegals.push(c);
                 continue 'outer;
             }
         }
 
-        let _closer = stack.autocomplete_close();
         let score = stack
             .autocomplete_close()
             .chars()
             .fold(0i64, |mut score, c| {
-                score = score * 5;
+                score *= 5;
                 score += match c {
                     ')' => 1,
                     ']' => 2,
@@ -35,24 +78,7 @@ pub fn print_result(input: impl std::io::Read) -> Result<()> {
         high_scores.push(score);
     }
 
-    let sum: u64 = illegals
-        .iter()
-        .map(|&c| match c {
-            ')' => 3,
-            ']' => 57,
-            '}' => 1197,
-            '>' => 25137,
-            _ => panic!("unexpected illegal {}", c),
-        })
-        .sum();
-
-    println!("Sum = {}", sum);
-
-    high_scores.sort();
-    let mid_score = high_scores.get((high_scores.len() + 0) / 2).unwrap();
-    println!("Middle score = {}", mid_score);
-
-    Ok(())
+    (illegals, high_scores)
 }
 
 struct ChunkStack {
@@ -90,7 +116,6 @@ impl ChunkStack {
 }
 
 struct Chunk {
-    open: char,
     close: char,
 }
 
@@ -109,9 +134,9 @@ impl std::convert::TryFrom<char> for Chunk {
         let iter = CHUNK_OPENER.chars().zip(CHUNK_CLOSER.chars());
         for (open, close) in iter {
             if value == open {
-                return Ok(Chunk { open, close });
+                return Ok(Chunk { close });
             }
         }
-        return Err(anyhow::Error::msg("not a chunk open char"));
+        Err(anyhow::Error::msg("not a chunk open char"))
     }
 }
@@ -3,45 +3,66 @@ use std::collections::{HashMap, HashSet};
 use anyhow::Result;
 
 use super::input;
+use super::{Answer, Solution};
 
-pub fn print_result(input: impl std::io::Read) -> Result<()> {
-    let connections = parse_input(input)?;
+/// Solver solves the day 12 puzzle.
+pub struct Solver;
 
-    let first_cave = "start".to_owned();
-    let routes = find_routes(&connections, vec![], &first_cave, false);
+impl Solution for Solver {
+    const DAY: u8 = 12;
+    const TITLE: &'static str = "Passage Pathing";
 
-    println!("Part 1: Number of paths through caves = {}", routes.len());
+    type Input = HashMap<Cave, Vec<Cave>>;
 
-    let routes = find_routes(&connections, vec![], &first_cave, true);
+    fn parse(&self, input: impl std::io::Read) -> Result<Self::Input> {
+        parse_input(input)
+    }
 
-    println!("Part 2: Number of paths with revist = {}", routes.len());
+    fn part1(&self, connections: &Self::Input) -> Result<Answer> {
+        // number of paths through caves
+        Ok(count_routes(connections, false)?.into())
+    }
 
-    Ok(())
+    fn part2(&self, connections: &Self::Input) -> Result<Answer> {
+        // number of paths with revisit
+        Ok(count_routes(connections, true)?.into())
+    }
 }
 
-type Cave = String;
+pub type Cave = String;
 type Route = Vec<Cave>;
 
-fn parse_input(input: impl std::io::Read) -> Result<HashMap<Cave, Vec<Cave>>> {
-    let lines = input::get_input_lines(input)?;
+/// parse_input parses the `a-b` connections into a map from each cave to its neighbours.
+pub fn parse_input(input: impl std::io::Read) -> Result<HashMap<Cave, Vec<Cave>>> {
+    let lines = input::get_numbered_lines(input)?;
     let mut connections: HashMap<Cave, Vec<Cave>> = HashMap::new();
-    for line in lines {
+    for line in lines.iter().filter(|line| !line.is_blank()) {
         let (a, b) = line
+            .text
+            .trim()
             .split_once('-')
             .map(|(a, b)| (a.to_owned(), b.to_owned()))
-            .ok_or(anyhow::Error::msg("malformed input"))?;
+            .ok_or_else(|| line.error(line.field(), "expected 'a-b'"))?;
 
-        connections
-            .entry(a.clone())
-            .or_insert(Vec::new())
-            .push(b.clone());
-        connections.entry(b).or_insert(Vec::new()).push(a);
+        connections.entry(a.clone()).or_default().push(b.clone());
+        connections.entry(b).or_default().push(a);
     }
 
     Ok(connections)
 }
 
-fn find_routes(
+/// count_routes returns the number of routes from the start cave to the end cave.
+fn count_routes(conns: &HashMap<Cave, Vec<Cave>>, allow_revisit: bool) -> Result<usize> {
+    let first_cave = "start".to_owned();
+    if !conns.contains_key(&first_cave) {
+        return Err(anyhow::Error::msg("no start cave"));
+    }
+    Ok(find_routes(conns, vec![], &first_cave, allow_revisit).len())
+}
+
+/// find_routes returns every route from `next_cave` to the end cave. Small caves are only
+/// visited once, except one small cave may be visited twice if `allow_revisit` is set.
+pub(crate) fn find_routes(
     conns: &HashMap<Cave, Vec<Cave>>,
     mut visited: Route,
     next_cave: &Cave,
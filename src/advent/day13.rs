@@ -2,117 +2,132 @@ use std::{collections::HashSet, fmt::Write};
 
 use anyhow::Result;
 
-use super::input;
+use super::geometry::{BoundingBox, Point2};
+use super::input::{self, NumberedLine, ParseError};
+use super::{Answer, Solution};
 
-pub fn print_result(input: impl std::io::Read) -> Result<()> {
-    let (dots, folds) = parse_input(input)?;
+/// Solver solves the day 13 puzzle.
+pub struct Solver;
 
-    for dot in dots.iter() {
-        println!("{}, {}", dot.0, dot.1);
-    }
-    println!("\n");
-
-    let mut first = dots.clone();
-    let first_fold = folds.first().unwrap();
-    first = first
-        .into_iter()
-        .filter_map(|d| first_fold.update_dot_position(d))
-        .collect();
-    for dot in first.iter() {
-        println!("{}, {}", dot.0, dot.1);
+impl Solution for Solver {
+    const DAY: u8 = 13;
+    const TITLE: &'static str = "Transparent Origami";
+
+    type Input = (HashSet<Dot>, Vec<Fold>);
+
+    fn parse(&self, input: impl std::io::Read) -> Result<Self::Input> {
+        parse_input(input)
     }
-    println!("Part 1: Dots after first fold = {}", first.len());
 
-    let mut second = dots.clone();
-    for fold in folds.iter() {
-        second = second
-            .into_iter()
-            .filter_map(|d| fold.update_dot_position(d))
+    fn part1(&self, (dots, folds): &Self::Input) -> Result<Answer> {
+        // dots after first fold
+        let first_fold = folds
+            .first()
+            .ok_or_else(|| anyhow::Error::msg("no folds"))?;
+        let first: HashSet<Dot> = dots
+            .iter()
+            .filter_map(|&d| first_fold.update_dot_position(d))
             .collect();
+        Ok(first.len().into())
     }
-    render(second);
 
-    Ok(())
+    fn part2(&self, (dots, folds): &Self::Input) -> Result<Answer> {
+        let mut second = dots.clone();
+        for fold in folds.iter() {
+            second = second
+                .into_iter()
+                .filter_map(|d| fold.update_dot_position(d))
+                .collect();
+        }
+        Ok(render(&second).into())
+    }
 }
 
-type Dot = (i16, i16);
-enum Fold {
+/// Dot is the position of a dot on the paper.
+pub type Dot = Point2<i16>;
+/// Fold is a fold line of the transparent paper.
+pub enum Fold {
     X(i16),
     Y(i16),
 }
 
 impl Fold {
-    fn update_dot_position(&self, (dx, dy): Dot) -> Option<Dot> {
-        match &self {
+    /// update_dot_position returns where the dot ends up after folding, or None if it is on
+    /// the fold line.
+    pub fn update_dot_position(&self, dot: Dot) -> Option<Dot> {
+        match *self {
             Fold::X(x) => {
-                if *x == dx {
+                if x == dot.x {
                     None
-                } else if dx > *x {
-                    Some((x - (dx - x), dy))
+                } else if dot.x > x {
+                    Some(Dot::new(x - (dot.x - x), dot.y))
                 } else {
-                    Some((dx, dy))
+                    Some(dot)
                 }
             }
             Fold::Y(y) => {
-                if *y == dy {
+                if y == dot.y {
                     None
-                } else if dy > *y {
-                    Some((dx, y - (dy - y)))
+                } else if dot.y > y {
+                    Some(Dot::new(dot.x, y - (dot.y - y)))
                 } else {
-                    Some((dx, dy))
+                    Some(dot)
                 }
             }
         }
     }
 }
 
-fn parse_input(input: impl std::io::Read) -> Result<(HashSet<Dot>, Vec<Fold>)> {
-    let lines = input::get_input_lines(input)?;
-    let mut dots: HashSet<Dot> = HashSet::new();
-    let mut folds: Vec<Fold> = vec![];
-    let mut read_folds = false;
-    for line in lines {
-        if line == "" {
-            read_folds = true;
-            continue;
-        }
-        if !read_folds {
-            let dot: Dot = line
-                .split_once(',')
-                .map(|(a, b)| (a.parse().unwrap(), b.parse().unwrap()))
-                .unwrap();
-            dots.insert(dot);
-        } else {
-            parse_fold(&mut folds, line);
+/// parse_input parses the dots followed by the fold instructions.
+pub fn parse_input(input: impl std::io::Read) -> Result<(HashSet<Dot>, Vec<Fold>)> {
+    let sections = input::get_input_sections(input)?;
+    let (dot_lines, fold_lines) = match &sections[..] {
+        [dots, folds] => (dots, folds),
+        [dots] => (dots, &vec![]),
+        [_, _, extra, ..] => {
+            return Err(extra[0]
+                .error(extra[0].field(), "expected the end of the input")
+                .into())
         }
+        [] => return Err(ParseError::new(1, 1, "", "empty input").into()),
+    };
+
+    let mut dots: HashSet<Dot> = HashSet::new();
+    for line in dot_lines {
+        let (x, y) = line
+            .field()
+            .split_once(",")
+            .ok_or_else(|| line.error(line.field(), "expected 'x,y'"))?;
+        dots.insert(Dot::new(line.parse(x)?, line.parse(y)?));
     }
+    let folds = fold_lines
+        .iter()
+        .map(parse_fold)
+        .collect::<Result<_, _>>()?;
 
     Ok((dots, folds))
 }
 
-fn parse_fold(folds: &mut Vec<Fold>, line: String) {
-    match line.split_once("=") {
-        Some(("fold along x", x)) => folds.push(Fold::X(x.parse().unwrap())),
-        Some(("fold along y", y)) => folds.push(Fold::Y(y.parse().unwrap())),
-        None => return,
-        _ => return,
+fn parse_fold(line: &NumberedLine) -> Result<Fold, ParseError> {
+    let fold = line.field().trim().split_once("=");
+    match fold.map(|(axis, n)| (axis.text, n)) {
+        Some(("fold along x", x)) => Ok(Fold::X(line.parse(x)?)),
+        Some(("fold along y", y)) => Ok(Fold::Y(line.parse(y)?)),
+        _ => Err(line.error(
+            line.field(),
+            "expected 'fold along x=n' or 'fold along y=n'",
+        )),
     }
 }
 
-fn render(dots: HashSet<Dot>) {
-    let mut max: Dot = (0, 0);
-    for &dot in dots.iter() {
-        if dot.0 > max.0 {
-            max.0 = dot.0
-        }
-        if dot.1 > max.1 {
-            max.1 = dot.1
-        }
-    }
+/// render draws the dots as `#`, from the origin to the furthest dot.
+pub fn render(dots: &HashSet<Dot>) -> String {
+    let mut bounds = BoundingBox::new(Dot::default(), Dot::default());
+    dots.iter().for_each(|&dot| bounds.include(dot));
     let mut s = String::new();
-    for y in 0..=max.1 {
-        for x in 0..=max.0 {
-            if dots.contains(&(x, y)) {
+    for y in 0..=bounds.max.y {
+        for x in 0..=bounds.max.x {
+            if dots.contains(&Dot::new(x, y)) {
                 s.write_char('#').unwrap();
             } else {
                 s.write_char(' ').unwrap();
@@ -120,5 +135,25 @@ fn render(dots: HashSet<Dot>) {
         }
         s.write_char('\n').unwrap();
     }
-    println!("{}", s)
+    s
+}
+
+#[test]
+fn test_parse_input_error() {
+    use super::DynSolution;
+
+    let input = "6,10\n0,14\n\nfold along y=7\nfold along z=5\n";
+    let err = DynSolution::parse(&Solver, &mut input.as_bytes())
+        .err()
+        .unwrap()
+        .downcast::<ParseError>()
+        .unwrap();
+    assert_eq!(err.day, Some(13));
+    assert_eq!((err.line, err.column), (5, 1));
+
+    let err = parse_input("6,10\n0,-\n".as_bytes()).err().unwrap();
+    assert_eq!(
+        err.to_string(),
+        "line 2, column 3: invalid digit found in string: '-'"
+    );
 }
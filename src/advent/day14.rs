@@ -1,67 +1,139 @@
-use std::{collections::HashMap, hash::Hash};
+use std::collections::{HashMap, HashSet};
+use std::hash::Hash;
 
 use anyhow::Result;
 
-use super::input;
+use super::input::{self, ParseError};
+use super::{Answer, Solution};
 
-pub fn print_result(input: impl std::io::Read) -> Result<()> {
-    let (polymer_string, mapping) = parse_input(input).unwrap();
+/// Solver solves the day 14 puzzle.
+pub struct Solver;
 
-    let mut polymer = polymer_string.chars().collect::<Vec<char>>();
-    for _ in 0..10 {
-        polymer = process_polymer(&mapping, &polymer);
-        println!("{}", polymer.iter().collect::<String>());
-    }
-    println!(
-        "Part 1: {}",
-        calculate_the_thing(count_occurrance(&polymer).into_iter())
-    );
+impl Solution for Solver {
+    const DAY: u8 = 14;
+    const TITLE: &'static str = "Extended Polymerization";
 
-    let mut polymer: HashMap<PolymerPair, u64> = HashMap::new();
-    polymer_string
-        .chars()
-        .collect::<Vec<char>>()
-        .windows(2)
-        .map(slice_into_pair)
-        .for_each(|pair| *polymer.entry(pair).or_insert(0) += 1);
+    type Input = (String, HashMap<PolymerPair, PolymerTriple>);
 
-    let mut map: HashMap<char, u64> = HashMap::new();
-    polymer_string
-        .chars()
-        .for_each(|c| *map.entry(c).or_insert(0) += 1);
-    for _ in 0..40 {
-        process_polymer2(&mapping, &mut polymer, &mut map);
+    fn parse(&self, input: impl std::io::Read) -> Result<Self::Input> {
+        parse_input(input)
+    }
+
+    fn part1(&self, (polymer_string, mapping): &Self::Input) -> Result<Answer> {
+        let mut polymer = polymer_string.chars().collect::<Vec<char>>();
+        for _ in 0..10 {
+            polymer = process_polymer(mapping, &polymer);
+        }
+        Ok(calculate_the_thing(count_occurrance(&polymer).into_iter()).into())
     }
-    println!("Part 2: {}", calculate_the_thing(map.into_iter()));
 
-    Ok(())
+    fn part2(&self, (polymer_string, mapping): &Self::Input) -> Result<Answer> {
+        let mut polymer: HashMap<PolymerPair, u64> = HashMap::new();
+        polymer_string
+            .chars()
+            .collect::<Vec<char>>()
+            .windows(2)
+            .map(slice_into_pair)
+            .for_each(|pair| *polymer.entry(pair).or_insert(0) += 1);
+
+        let mut map: HashMap<char, u64> = HashMap::new();
+        polymer_string
+            .chars()
+            .for_each(|c| *map.entry(c).or_insert(0) += 1);
+        for _ in 0..40 {
+            process_polymer2(mapping, &mut polymer, &mut map);
+        }
+        Ok(calculate_the_thing(map.into_iter()).into())
+    }
 }
 
 fn calculate_the_thing(it: impl Iterator<Item = (char, u64)>) -> u64 {
     let mut v: Vec<(char, u64)> = it.collect();
-    v.sort_by(|a, b| b.1.cmp(&a.1));
+    v.sort_by_key(|&(_, n)| std::cmp::Reverse(n));
     v.first().unwrap().1 - v.last().unwrap().1
 }
 
-fn parse_input(input: impl std::io::Read) -> Result<(String, HashMap<PolymerPair, PolymerTriple>)> {
-    let lines = input::get_input_lines(input)?;
-    let polymer = lines.get(0).unwrap().clone();
+/// parse_input parses the polymer template and the pair insertion rules.
+pub fn parse_input(
+    input: impl std::io::Read,
+) -> Result<(String, HashMap<PolymerPair, PolymerTriple>)> {
+    let sections = input::get_input_sections(input)?;
+    let (template, rules) = match &sections[..] {
+        [template, rules] => (template, rules),
+        [template] => {
+            let last = &template[template.len() - 1];
+            return Err(ParseError::new(last.number + 1, 1, "", "expected insertion rules").into());
+        }
+        [_, _, extra, ..] => {
+            return Err(extra[0]
+                .error(extra[0].field(), "expected the end of the input")
+                .into())
+        }
+        [] => return Err(ParseError::new(1, 1, "", "empty input").into()),
+    };
+    if let [_, extra, ..] = &template[..] {
+        return Err(extra.error(extra.field(), "expected a blank line").into());
+    }
+    let template = &template[0];
+    let polymer = template.field().trim();
+
     let mut mapping: HashMap<PolymerPair, PolymerTriple> = HashMap::new();
-    for line in lines.iter().skip(2) {
-        let (from, insert) = line.split_once(" -> ").unwrap();
-        let from_chars = PolymerPair(from.chars().next().unwrap(), from.chars().last().unwrap());
-        let to_chars = PolymerTriple(
-            from.chars().next().unwrap(),
-            insert.chars().next().unwrap(),
-            from.chars().last().unwrap(),
-        );
-        mapping.insert(from_chars, to_chars);
+    // inserts are the lines and fields of the inserted elements, to locate missing rules.
+    let mut inserts = HashMap::new();
+    for line in rules {
+        let (from, insert) = line.rule()?;
+        let (a, b) = match from.text.chars().collect::<Vec<_>>()[..] {
+            [a, b] => (a, b),
+            _ => return Err(line.error(from, "expected a pair").into()),
+        };
+        let c = match insert.text.chars().collect::<Vec<_>>()[..] {
+            [c] => c,
+            _ => return Err(line.error(insert, "expected an element").into()),
+        };
+        mapping.insert(PolymerPair(a, b), PolymerTriple(a, c, b));
+        inserts.insert(PolymerPair(a, b), (line, insert));
+    }
+
+    // Every pair that can appear in the polymer needs a rule: those of the template and,
+    // in turn, the pairs made by inserting elements.
+    let chars = polymer.text.char_indices().collect::<Vec<_>>();
+    let mut pending = chars
+        .windows(2)
+        .rev()
+        .map(|w| {
+            let ((i, a), (j, b)) = (w[0], w[1]);
+            (
+                PolymerPair(a, b),
+                template,
+                polymer.slice(i..j + b.len_utf8()),
+            )
+        })
+        .collect::<Vec<_>>();
+    let mut checked = HashSet::new();
+    while let Some((pair, line, field)) = pending.pop() {
+        if !checked.insert(pair) {
+            continue;
+        }
+        let triple = mapping.get(&pair).ok_or_else(|| {
+            line.error(
+                field,
+                format!("no insertion rule for pair '{}{}'", pair.0, pair.1),
+            )
+        })?;
+        let (line, insert) = inserts[&pair];
+        let (p1, p2) = triple.pairs();
+        pending.push((p2, line, insert));
+        pending.push((p1, line, insert));
     }
 
-    Ok((polymer, mapping))
+    Ok((polymer.text.to_owned(), mapping))
 }
 
-fn process_polymer(mapping: &HashMap<PolymerPair, PolymerTriple>, polymer: &[char]) -> Vec<char> {
+/// process_polymer applies one step of pair insertion to the polymer.
+pub(crate) fn process_polymer(
+    mapping: &HashMap<PolymerPair, PolymerTriple>,
+    polymer: &[char],
+) -> Vec<char> {
     flatten(
         polymer
             .windows(2)
@@ -84,7 +156,8 @@ fn flatten(triples: impl Iterator<Item = impl Iterator<Item = char>>) -> Vec<cha
     vec
 }
 
-fn count_occurrance(polymer: &[char]) -> HashMap<char, u64> {
+/// count_occurrance counts the elements of the polymer.
+pub(crate) fn count_occurrance(polymer: &[char]) -> HashMap<char, u64> {
     let mut map: HashMap<char, u64> = HashMap::new();
     for c in polymer {
         *map.entry(*c).or_insert(0) += 1;
@@ -114,11 +187,13 @@ fn slice_into_pair(pair: &[char]) -> PolymerPair {
     PolymerPair(pair[0], pair[1])
 }
 
+/// PolymerPair is a pair of adjacent elements.
 #[derive(Clone, Copy, PartialEq, Eq, Hash)]
-struct PolymerPair(char, char);
+pub struct PolymerPair(char, char);
 
+/// PolymerTriple is a pair with the element inserted between them.
 #[derive(Clone, Copy, PartialEq, Eq, Hash)]
-struct PolymerTriple(char, char, char);
+pub struct PolymerTriple(char, char, char);
 
 impl PolymerTriple {
     /// certainly there must be some tuple iterator somewhere already??
@@ -146,3 +221,26 @@ impl PolymerTriple {
         self.1
     }
 }
+
+#[test]
+fn test_parse_input_missing_rule() {
+    let err = parse_input("NNCB\n\nCH -> B\n".as_bytes()).err().unwrap();
+    assert_eq!(
+        err.to_string(),
+        "line 1, column 1: no insertion rule for pair 'NN': 'NN'"
+    );
+
+    // Pairs made by insertions need rules too.
+    let err = parse_input("NC\n\nNC -> B\nNB -> C\n".as_bytes())
+        .err()
+        .unwrap();
+    assert_eq!(
+        err.to_string(),
+        "line 4, column 7: no insertion rule for pair 'CB': 'C'"
+    );
+
+    let (polymer, mapping) =
+        parse_input("NC\n\nNC -> N\nNN -> C\nCN -> C\nCC -> N\n".as_bytes()).unwrap();
+    assert_eq!(polymer, "NC");
+    assert_eq!(mapping.len(), 4);
+}
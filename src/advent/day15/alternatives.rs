@@ -0,0 +1,142 @@
+//! Alternative routes, the k lowest risk paths that don't visit a position twice, found
+//! with Yen's algorithm.
+
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashSet};
+
+use super::map::RiskMap;
+use super::search::{Algorithm, PathFinder, Step};
+use super::Risk;
+use crate::advent::grid::Position;
+
+/// Alternative is one of the k lowest risk paths.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Alternative {
+    /// risk is the total risk of the path, excluding the start position.
+    pub risk: Risk,
+    /// path is the steps from start to goal, both included.
+    pub path: Vec<Step>,
+    /// shared is the number of positions of the path that are also on the lowest risk
+    /// path, start and goal included.
+    pub shared: usize,
+}
+
+impl Alternative {
+    /// positions returns the positions of the path, from start to goal.
+    pub fn positions(&self) -> impl Iterator<Item = Position> + '_ {
+        self.path.iter().map(|step| step.pos)
+    }
+}
+
+impl<M: RiskMap> PathFinder<M> {
+    /// k_shortest_paths returns the `k` lowest risk paths from start to goal that don't
+    /// visit a position twice, lowest risk first. Fewer are returned if there aren't `k`.
+    pub fn k_shortest_paths(&self, start: Position, goal: Position, k: usize) -> Vec<Alternative> {
+        if k == 0 {
+            return vec![];
+        }
+        let mut found: Vec<Vec<Step>> = match self.search(start, goal, Algorithm::AStar) {
+            Some(route) => vec![route.path],
+            None => return vec![],
+        };
+
+        // `candidates` are the paths found deviating from the paths found so far, lowest
+        // risk first, and `seen` the positions of every path found or a candidate.
+        let mut candidates: BinaryHeap<Reverse<(Risk, Vec<Step>)>> = BinaryHeap::new();
+        let mut seen: HashSet<Vec<Position>> = HashSet::new();
+        seen.insert(found[0].iter().map(|step| step.pos).collect());
+
+        while found.len() < k {
+            let last = found.last().unwrap();
+            // Deviate from the last path at every position but the goal, the spur, keeping
+            // the path up to it, the root.
+            for i in 0..last.len() - 1 {
+                let root = &last[..=i];
+                let spur = root[i];
+
+                // Don't repeat the next move of any path found with the same root, nor
+                // visit the root again.
+                let removed: HashSet<(Position, Position)> = found
+                    .iter()
+                    .filter(|path| path.len() > i + 1 && same_positions(&path[..=i], root))
+                    .map(|path| (path[i].pos, path[i + 1].pos))
+                    .collect();
+                let visited: HashSet<Position> = root[..i].iter().map(|step| step.pos).collect();
+                let allowed = |from, to| !visited.contains(&to) && !removed.contains(&(from, to));
+
+                let route = match self.search_with(spur.pos, goal, Algorithm::AStar, allowed) {
+                    Some(route) => route,
+                    None => continue,
+                };
+                let mut path = root.to_vec();
+                path.extend(route.path[1..].iter().map(|step| Step {
+                    total: spur.total + step.total,
+                    ..*step
+                }));
+                if seen.insert(path.iter().map(|step| step.pos).collect()) {
+                    candidates.push(Reverse((spur.total + route.risk, path)));
+                }
+            }
+
+            match candidates.pop() {
+                Some(Reverse((_, path))) => found.push(path),
+                None => break,
+            }
+        }
+
+        let lowest: HashSet<Position> = found[0].iter().map(|step| step.pos).collect();
+        found
+            .into_iter()
+            .map(|path| Alternative {
+                risk: path.last().unwrap().total,
+                shared: path
+                    .iter()
+                    .filter(|step| lowest.contains(&step.pos))
+                    .count(),
+                path,
+            })
+            .collect()
+    }
+}
+
+fn same_positions(a: &[Step], b: &[Step]) -> bool {
+    a.iter()
+        .map(|step| step.pos)
+        .eq(b.iter().map(|step| step.pos))
+}
+
+#[test]
+fn test_k_shortest_paths() {
+    use crate::advent::grid::Grid;
+
+    // Every path from corner to corner of 3x3 positions of risk 1 makes 4 or at least 6
+    // moves.
+    let finder = PathFinder::new(Grid::filled(3, 3, 1u8));
+    let paths = finder.k_shortest_paths(Position(0, 0), Position(2, 2), 10);
+    assert_eq!(paths.len(), 10);
+    let risks = paths.iter().map(|path| path.risk).collect::<Vec<_>>();
+    assert_eq!(risks, [4, 4, 4, 4, 4, 4, 6, 6, 6, 6]);
+    let distinct: HashSet<Vec<Position>> = paths.iter().map(|p| p.positions().collect()).collect();
+    assert_eq!(distinct.len(), 10);
+    for path in &paths {
+        let positions: HashSet<Position> = path.positions().collect();
+        assert_eq!(positions.len(), path.path.len());
+        assert_eq!(path.path.first().unwrap().pos, Position(0, 0));
+        assert_eq!(path.path.last().unwrap().pos, Position(2, 2));
+        for pair in path.path.windows(2) {
+            assert_eq!(pair[1].total, pair[0].total + pair[1].risk);
+        }
+    }
+    assert_eq!(paths[0].shared, 5);
+    assert!(paths[1..].iter().all(|path| path.shared < path.path.len()));
+
+    // There are only 2 paths through a ring.
+    let ring = Grid::parse_digits("111\n101\n111\n".as_bytes()).unwrap();
+    let paths = PathFinder::new(ring).k_shortest_paths(Position(0, 0), Position(2, 2), 5);
+    let summary = paths.iter().map(|p| (p.risk, p.shared)).collect::<Vec<_>>();
+    assert_eq!(summary, [(4, 5), (4, 2)]);
+
+    assert!(finder
+        .k_shortest_paths(Position(0, 0), Position(2, 2), 0)
+        .is_empty());
+}
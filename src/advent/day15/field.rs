@@ -0,0 +1,154 @@
+//! Distance fields, the lowest total risk to every position of a map from the nearest of
+//! some sources, answering any number of goal queries with a single search.
+
+use std::collections::HashMap;
+
+use super::map::RiskMap;
+use super::movement::Movement;
+use super::search::{Algorithm, PathFinder, SearchStats, Step};
+use super::Risk;
+use crate::advent::grid::{Grid, Position};
+
+/// UNREACHED is the total risk to positions that can't be reached.
+pub const UNREACHED: Risk = Risk::MAX;
+
+/// NO_MOVE marks positions that weren't reached from another position.
+pub(super) const NO_MOVE: u8 = u8::MAX;
+
+/// HEATMAP are the characters of a rendered field, from the lowest to the highest risk.
+const HEATMAP: &[u8] = b".:-=+*#%@";
+
+/// DistanceField is the lowest total risk to every position from the nearest source.
+pub struct DistanceField {
+    /// size is the width and height of the map.
+    size: (usize, usize),
+    /// reached is the lowest total risk to every position reached and the index of the
+    /// move it was reached with, NO_MOVE for the sources.
+    reached: HashMap<Position, (Risk, u8)>,
+    movement: Movement,
+    pub stats: SearchStats,
+}
+
+impl DistanceField {
+    pub(super) fn new(
+        size: (usize, usize),
+        reached: HashMap<Position, (Risk, u8)>,
+        movement: Movement,
+        stats: SearchStats,
+    ) -> Self {
+        Self {
+            size,
+            reached,
+            movement,
+            stats,
+        }
+    }
+
+    /// risk returns the lowest total risk to `pos`, None if it can't be reached.
+    pub fn risk(&self, pos: Position) -> Option<Risk> {
+        self.reached.get(&pos).map(|&(risk, _)| risk)
+    }
+
+    /// grid returns the lowest total risk to every position, UNREACHED for those that
+    /// can't be reached.
+    pub fn grid(&self) -> Grid<Risk> {
+        let mut grid = Grid::filled(self.size.0, self.size.1, UNREACHED);
+        for (&pos, &(risk, _)) in &self.reached {
+            grid[pos] = risk;
+        }
+        grid
+    }
+
+    /// path returns the steps of the lowest risk path from the nearest source to `pos`,
+    /// None if it can't be reached.
+    pub fn path(&self, pos: Position) -> Option<Vec<Step>> {
+        self.risk(pos)?;
+        let mut steps = vec![];
+        let mut pos = pos;
+        loop {
+            let (total, prev) = self.reached[&pos];
+            let from = match prev {
+                NO_MOVE => None,
+                i => {
+                    let (dx, dy) = self.movement.moves()[i as usize].offset;
+                    Some(pos.offset((-dx, -dy)).expect("a move from inside the map"))
+                }
+            };
+            let risk = from.map_or(0, |from| total - self.reached[&from].0);
+            steps.push(Step { pos, risk, total });
+            match from {
+                Some(from) => pos = from,
+                None => break,
+            }
+        }
+        steps.reverse();
+        Some(steps)
+    }
+
+    /// render renders the field as a heatmap, from `.` for the lowest risk to `@` for the
+    /// highest. Positions that can't be reached are blank.
+    pub fn render(&self) -> String {
+        let max = self
+            .reached
+            .values()
+            .map(|&(risk, _)| risk)
+            .max()
+            .unwrap_or(0);
+        self.grid().render(|&risk| match risk {
+            UNREACHED => ' ',
+            _ if max == 0 => HEATMAP[0] as char,
+            _ => {
+                let level = risk as u64 * (HEATMAP.len() - 1) as u64 / max as u64;
+                HEATMAP[level as usize] as char
+            }
+        })
+    }
+}
+
+impl<M: RiskMap> PathFinder<M> {
+    /// distance_field returns the lowest total risk from the nearest of the sources to
+    /// every position, None if a source is outside the map.
+    pub fn distance_field(&self, sources: &[Position]) -> Option<DistanceField> {
+        self.explore(sources, None, Algorithm::Dijkstra, |_, _| true)
+    }
+}
+
+#[test]
+fn test_distance_field() {
+    let map = Grid::parse_digits("11911\n19191\n11111\n".as_bytes()).unwrap();
+    let finder = PathFinder::new(map);
+    let field = finder.distance_field(&[Position(0, 0)]).unwrap();
+    assert_eq!(field.risk(Position(0, 0)), Some(0));
+    assert_eq!(field.risk(Position(4, 0)), Some(8));
+    assert_eq!(field.risk(Position(5, 0)), None);
+    assert_eq!(field.grid().row(2), Some(&[2, 3, 4, 5, 6][..]));
+    assert_eq!(field.stats.expanded, 15);
+
+    // The paths agree with searching for every goal.
+    for goal in field.grid().positions() {
+        let route = finder
+            .search(Position(0, 0), goal, Algorithm::AStar)
+            .unwrap();
+        let path = field.path(goal).unwrap();
+        assert_eq!(path.last().unwrap().total, route.risk);
+        assert_eq!(path.first().unwrap().pos, Position(0, 0));
+        for pair in path.windows(2) {
+            assert_eq!(pair[1].total, pair[0].total + pair[1].risk);
+        }
+    }
+    assert_eq!(field.render(), "..**+\n.*-@+\n::--=\n");
+
+    // With more sources, the nearest one counts.
+    let field = finder
+        .distance_field(&[Position(0, 0), Position(4, 2)])
+        .unwrap();
+    assert_eq!(field.grid().row(0), Some(&[0, 1, 10, 3, 2][..]));
+    assert_eq!(field.path(Position(4, 0)).unwrap()[0].pos, Position(4, 2));
+
+    // Walls and positions cut off by them are never reached.
+    let walled = PathFinder::new(Grid::parse_digits("101\n101\n".as_bytes()).unwrap());
+    let field = walled.distance_field(&[Position(0, 0)]).unwrap();
+    assert_eq!(field.risk(Position(2, 0)), None);
+    assert!(field.path(Position(2, 1)).is_none());
+    assert_eq!(field.render(), ".  \n@  \n");
+}
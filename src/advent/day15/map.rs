@@ -0,0 +1,173 @@
+//! Risk maps of the cave, as read or tiled on lookup.
+
+use anyhow::Result;
+
+use crate::advent::grid::{Grid, Position};
+
+/// tiled_risk is the risk of a position copied to tile `(tile_x, tile_y)`, wrapping from 9
+/// back to 1. Impassable positions, with a risk of 0, stay impassable.
+pub(super) fn tiled_risk(risk: u8, tile_x: usize, tile_y: usize) -> u8 {
+    if risk == 0 {
+        return 0;
+    }
+    ((risk as usize + tile_x + tile_y - 1) % 9 + 1) as u8
+}
+
+/// RiskMap is a map of the risk of every position in the cave.
+pub trait RiskMap {
+    fn width(&self) -> usize;
+    fn height(&self) -> usize;
+    /// risk returns the risk of a position in the map, 0 if it can't be entered.
+    fn risk(&self, pos: Position) -> u8;
+    /// min_risk returns the lowest risk of any position that can be entered, 0 if there
+    /// are none.
+    fn min_risk(&self) -> u8;
+
+    /// max returns the bottom right position, the origin if the map is empty.
+    fn max(&self) -> Position {
+        Position(
+            self.width().saturating_sub(1),
+            self.height().saturating_sub(1),
+        )
+    }
+
+    fn contains(&self, pos: Position) -> bool {
+        pos.0 < self.width() && pos.1 < self.height()
+    }
+}
+
+impl RiskMap for Grid<u8> {
+    fn width(&self) -> usize {
+        Grid::width(self)
+    }
+
+    fn height(&self) -> usize {
+        Grid::height(self)
+    }
+
+    fn risk(&self, pos: Position) -> u8 {
+        self[pos]
+    }
+
+    fn min_risk(&self) -> u8 {
+        self.iter()
+            .copied()
+            .filter(|&risk| risk > 0)
+            .min()
+            .unwrap_or(0)
+    }
+}
+
+/// TiledMap is a map repeated `tiles_x` times to the right and `tiles_y` times down, its
+/// risk increasing by one per tile. Risks are computed on lookup, so that large tilings
+/// don't need the memory of `add_tiles`.
+pub struct TiledMap {
+    tile: Grid<u8>,
+    tiles_x: usize,
+    tiles_y: usize,
+}
+
+impl TiledMap {
+    /// new tiles the map, failing if there would be no positions or more than fit a
+    /// Position.
+    pub fn new(tile: Grid<u8>, tiles_x: usize, tiles_y: usize) -> Result<Self> {
+        if tile.is_empty() || tiles_x == 0 || tiles_y == 0 {
+            return Err(anyhow::anyhow!(
+                "can not tile a {}x{} map {}x{} times",
+                tile.width(),
+                tile.height(),
+                tiles_x,
+                tiles_y
+            ));
+        }
+        let fits = tile.width().checked_mul(tiles_x).is_some()
+            && tile.height().checked_mul(tiles_y).is_some();
+        if !fits {
+            return Err(anyhow::anyhow!(
+                "{}x{} tiles of the map are too large",
+                tiles_x,
+                tiles_y
+            ));
+        }
+        Ok(Self {
+            tile,
+            tiles_x,
+            tiles_y,
+        })
+    }
+
+    /// to_grid returns the tiled map with every risk computed.
+    pub fn to_grid(&self) -> Grid<u8> {
+        self.tile
+            .tile(self.tiles_x, self.tiles_y, |&risk, tile_x, tile_y| {
+                tiled_risk(risk, tile_x, tile_y)
+            })
+    }
+}
+
+impl RiskMap for TiledMap {
+    fn width(&self) -> usize {
+        self.tile.width() * self.tiles_x
+    }
+
+    fn height(&self) -> usize {
+        self.tile.height() * self.tiles_y
+    }
+
+    fn risk(&self, pos: Position) -> u8 {
+        let (width, height) = (self.tile.width(), self.tile.height());
+        let risk = self.tile[Position(pos.0 % width, pos.1 % height)];
+        tiled_risk(risk, pos.0 / width, pos.1 / height)
+    }
+
+    fn min_risk(&self) -> u8 {
+        // Tiles only differ by how much is added to the risk, at most 9 different amounts.
+        let increments = std::cmp::min(self.tiles_x + self.tiles_y - 1, 9);
+        (0..increments)
+            .flat_map(|i| self.tile.iter().map(move |&risk| tiled_risk(risk, i, 0)))
+            .filter(|&risk| risk > 0)
+            .min()
+            .unwrap_or(0)
+    }
+}
+
+#[test]
+fn test_tiled_map() {
+    use super::{add_tiles, lowest_total_risk};
+
+    let map = Grid::parse_digits("19\n83\n".as_bytes()).unwrap();
+    let tiled = TiledMap::new(map.clone(), 5, 5).unwrap();
+    assert_eq!(tiled.to_grid(), add_tiles(&map));
+    assert_eq!((tiled.width(), tiled.height()), (10, 10));
+    assert_eq!(tiled.risk(Position(3, 0)), 1);
+    assert_eq!(tiled.risk(Position(9, 9)), 2);
+    assert_eq!(tiled.min_risk(), 1);
+
+    let tiled = TiledMap::new(Grid::new(vec![5, 6], 2), 3, 1).unwrap();
+    assert_eq!(tiled.to_grid().row(0), Some(&[5, 6, 6, 7, 7, 8][..]));
+    assert_eq!(tiled.min_risk(), 5);
+
+    // The same lowest total risk as the materialized map, for any number of tiles.
+    for (tiles_x, tiles_y) in [(5, 5), (2, 7), (12, 1)] {
+        let tiled = TiledMap::new(map.clone(), tiles_x, tiles_y).unwrap();
+        let full = tiled.to_grid();
+        assert_eq!(
+            lowest_total_risk(tiled).unwrap(),
+            lowest_total_risk(full).unwrap()
+        );
+    }
+
+    // Empty maps have no path, and tiling them or tiling no times is an error.
+    let empty = Grid::<u8>::new(vec![], 0);
+    assert_eq!(empty.max(), Position(0, 0));
+    let err = lowest_total_risk(empty.clone()).unwrap_err();
+    assert_eq!(err.to_string(), "no path found");
+    let err = TiledMap::new(empty, 5, 5).err().unwrap();
+    assert_eq!(err.to_string(), "can not tile a 0x0 map 5x5 times");
+    assert!(TiledMap::new(map.clone(), 0, 5).is_err());
+    let err = TiledMap::new(map, usize::MAX, 1).err().unwrap();
+    assert_eq!(
+        err.to_string(),
+        format!("{}x1 tiles of the map are too large", usize::MAX)
+    );
+}
@@ -0,0 +1,68 @@
+use super::grid::{Grid, Position};
+use super::input::ParseError;
+use super::{Answer, Solution};
+use anyhow::Result;
+
+pub mod alternatives;
+pub mod field;
+pub mod map;
+pub mod movement;
+pub mod replan;
+pub mod search;
+
+use map::{tiled_risk, RiskMap, TiledMap};
+use search::{Algorithm, PathFinder};
+
+/// Solver solves the day 15 puzzle.
+pub struct Solver;
+
+impl Solution for Solver {
+    const DAY: u8 = 15;
+    const TITLE: &'static str = "Chiton";
+
+    type Input = Grid<u8>;
+
+    fn parse(&self, input: impl std::io::Read) -> Result<Self::Input> {
+        let map = Grid::parse_digits(input)?;
+        if map.is_empty() {
+            return Err(ParseError::new(1, 1, "", "empty input").into());
+        }
+        Ok(map)
+    }
+
+    fn part1(&self, map: &Self::Input) -> Result<Answer> {
+        Ok(lowest_total_risk(map.clone())?.into())
+    }
+
+    fn part2(&self, map: &Self::Input) -> Result<Answer> {
+        Ok(lowest_total_risk(TiledMap::new(map.clone(), 5, 5)?)?.into())
+    }
+}
+
+/// lowest_total_risk returns the lowest total risk from the top left to the bottom right.
+pub fn lowest_total_risk(map: impl RiskMap) -> Result<Risk> {
+    let goal = map.max();
+    let route = PathFinder::new(map)
+        .search(Position(0, 0), goal, Algorithm::AStar)
+        .ok_or_else(|| anyhow::Error::msg("no path found"))?;
+    Ok(route.risk)
+}
+
+/// add_tiles expands the map to the full 5x5 tiled cave, increasing risk by one per tile.
+pub fn add_tiles(map: &Grid<u8>) -> Grid<u8> {
+    map.tile(5, 5, |&risk, tile_x, tile_y| {
+        tiled_risk(risk, tile_x, tile_y)
+    })
+}
+
+/// Risk is a total risk along a path.
+pub type Risk = u32;
+
+#[test]
+fn test_add_tiles() {
+    let map = Grid::new(vec![8u8], 1);
+    let full = add_tiles(&map);
+    assert_eq!((full.width(), full.height()), (5, 5));
+    assert_eq!(full.row(0), Some(&[8, 9, 1, 2, 3][..]));
+    assert_eq!(full[Position(4, 4)], 7);
+}
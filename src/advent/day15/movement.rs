@@ -0,0 +1,174 @@
+//! The moves a path can make from one position to the next.
+
+use anyhow::Result;
+
+use super::Risk;
+use crate::advent::grid::{NEIGHBOURS4, NEIGHBOURS8};
+
+/// KNIGHT_MOVES are the offsets of the moves of a chess knight.
+pub const KNIGHT_MOVES: [(isize, isize); 8] = [
+    (1, 2),
+    (2, 1),
+    (2, -1),
+    (1, -2),
+    (-1, -2),
+    (-2, -1),
+    (-2, 1),
+    (-1, 2),
+];
+
+/// Move is a single move, the risk of the position moved to multiplied by its cost.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Move {
+    pub offset: (isize, isize),
+    pub cost: Risk,
+}
+
+/// Movement is the set of moves a path can make.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Movement {
+    moves: Vec<Move>,
+}
+
+impl Movement {
+    /// MAX_MOVES is the most moves a movement can have, so that the move to every position
+    /// can be stored in a byte.
+    pub const MAX_MOVES: usize = 255;
+
+    /// MAX_COST is the highest cost of a move, so that the risk of any move is below
+    /// Risk::MAX, which marks unreached positions.
+    pub const MAX_COST: Risk = (Risk::MAX - 1) / u8::MAX as Risk;
+
+    /// new returns the movement with the given moves. Moves must be distinct, cost 1 to
+    /// MAX_COST and not stay in place.
+    pub fn new(moves: Vec<Move>) -> Result<Self> {
+        if moves.is_empty() || moves.len() > Self::MAX_MOVES {
+            return Err(anyhow::anyhow!(
+                "expected 1 to {} moves, got {}",
+                Self::MAX_MOVES,
+                moves.len()
+            ));
+        }
+        for (i, m) in moves.iter().enumerate() {
+            if m.offset == (0, 0) {
+                return Err(anyhow::anyhow!("move {} stays in place", i));
+            }
+            if m.cost == 0 {
+                return Err(anyhow::anyhow!("move {} has no cost", i));
+            }
+            if m.cost > Self::MAX_COST {
+                return Err(anyhow::anyhow!(
+                    "move {} costs {}, more than {}",
+                    i,
+                    m.cost,
+                    Self::MAX_COST
+                ));
+            }
+            if moves[..i].iter().any(|other| other.offset == m.offset) {
+                return Err(anyhow::anyhow!("move {} repeats offset {:?}", i, m.offset));
+            }
+        }
+        Ok(Self { moves })
+    }
+
+    /// four_way moves right, down, left and up.
+    pub fn four_way() -> Self {
+        Self::custom(&NEIGHBOURS4).unwrap()
+    }
+
+    /// eight_way also moves diagonally, at `diagonal_cost` times the risk of the position.
+    pub fn eight_way(diagonal_cost: Risk) -> Result<Self> {
+        let moves = NEIGHBOURS8.iter().map(|&offset| Move {
+            offset,
+            cost: if offset.0 != 0 && offset.1 != 0 {
+                diagonal_cost
+            } else {
+                1
+            },
+        });
+        Self::new(moves.collect())
+    }
+
+    /// custom moves by the given offsets, each at the risk of the position.
+    pub fn custom(offsets: &[(isize, isize)]) -> Result<Self> {
+        let moves = offsets.iter().map(|&offset| Move { offset, cost: 1 });
+        Self::new(moves.collect())
+    }
+
+    pub fn moves(&self) -> &[Move] {
+        &self.moves
+    }
+
+    /// min_steps is a lower bound of the number of moves from one position to another
+    /// `(dx, dy)` away, given how far a single move can go.
+    pub fn min_steps(&self, (dx, dy): (usize, usize)) -> Risk {
+        let reach = |f: fn(usize, usize) -> usize| {
+            let longest = self
+                .moves
+                .iter()
+                .map(|m| f(m.offset.0.unsigned_abs(), m.offset.1.unsigned_abs()))
+                .max()
+                .unwrap_or(1);
+            (f(dx, dy) as f64 / longest as f64).ceil() as Risk
+        };
+        let manhattan = reach(|x, y| x + y);
+        let chebyshev = reach(std::cmp::max);
+        std::cmp::max(manhattan, chebyshev)
+    }
+
+    /// min_cost is the lowest cost of any move.
+    pub fn min_cost(&self) -> Risk {
+        self.moves.iter().map(|m| m.cost).min().unwrap_or(1)
+    }
+}
+
+impl Default for Movement {
+    fn default() -> Self {
+        Self::four_way()
+    }
+}
+
+#[test]
+fn test_movement() {
+    assert_eq!(Movement::default().moves().len(), 4);
+    let eight = Movement::eight_way(2).unwrap();
+    assert_eq!(
+        eight.moves()[0],
+        Move {
+            offset: (1, 0),
+            cost: 1
+        }
+    );
+    assert_eq!(
+        eight.moves()[7],
+        Move {
+            offset: (1, 1),
+            cost: 2
+        }
+    );
+    assert_eq!(eight.min_cost(), 1);
+
+    assert_eq!(Movement::four_way().min_steps((3, 4)), 7);
+    assert_eq!(eight.min_steps((3, 4)), 4);
+    let knight = Movement::custom(&KNIGHT_MOVES).unwrap();
+    assert_eq!(knight.min_steps((4, 2)), 2);
+    assert_eq!(knight.min_steps((7, 0)), 4);
+
+    let err = |offsets: &[(isize, isize)]| Movement::custom(offsets).unwrap_err().to_string();
+    assert_eq!(err(&[]), "expected 1 to 255 moves, got 0");
+    assert_eq!(err(&[(1, 0), (0, 0)]), "move 1 stays in place");
+    assert_eq!(err(&[(1, 0), (1, 0)]), "move 1 repeats offset (1, 0)");
+    assert_eq!(
+        Movement::eight_way(0).unwrap_err().to_string(),
+        "move 4 has no cost"
+    );
+    assert!(Movement::eight_way(Movement::MAX_COST).is_ok());
+    assert_eq!(
+        Movement::eight_way(Risk::MAX).unwrap_err().to_string(),
+        format!(
+            "move 4 costs {}, more than {}",
+            Risk::MAX,
+            Movement::MAX_COST
+        )
+    );
+}
@@ -0,0 +1,348 @@
+//! Incremental replanning with Lifelong Planning A* (LPA*), which keeps the risk to every
+//! position between searches, so that after a few risks change only the positions whose
+//! lowest risk changed are searched again.
+
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+
+use anyhow::Result;
+
+use super::field::UNREACHED;
+use super::movement::{Move, Movement};
+use super::search::{Route, SearchStats, Step};
+use super::Risk;
+use crate::advent::geometry::Point2;
+use crate::advent::grid::{Grid, Position};
+
+/// Key orders positions in the queue, lowest first.
+type Key = (Risk, Risk);
+
+/// MIN_COMPACT_AT is the fewest queue entries the queue is compacted at.
+const MIN_COMPACT_AT: usize = 64;
+
+/// Replanner finds the lowest risk route between two fixed positions of a map whose risks
+/// change. Positions with a risk of 0 can't be moved to.
+pub struct Replanner {
+    map: Grid<u8>,
+    movement: Movement,
+    start: Position,
+    goal: Position,
+    /// g is the lowest risk to every position found by the last search.
+    g: Grid<Risk>,
+    /// rhs is the lowest risk to every position through its predecessors' `g`. Positions
+    /// where it differs from `g` are inconsistent and need searching.
+    rhs: Grid<Risk>,
+    /// queue holds the inconsistent positions. Entries whose key is out of date are
+    /// skipped when popped.
+    queue: BinaryHeap<Reverse<(Key, Position)>>,
+    /// compact_at is the length of the queue it is compacted at, dropping the entries that
+    /// are out of date, which may otherwise never be popped.
+    compact_at: usize,
+    stats: SearchStats,
+}
+
+impl Replanner {
+    /// new returns a replanner from start to goal, failing if either is outside the map.
+    pub fn new(map: Grid<u8>, movement: Movement, start: Position, goal: Position) -> Result<Self> {
+        for (name, pos) in [("start", start), ("goal", goal)] {
+            if !map.contains(pos) {
+                return Err(anyhow::anyhow!("{} {:?} is outside the map", name, pos));
+            }
+        }
+        let (width, height) = (map.width(), map.height());
+        let mut planner = Self {
+            map,
+            movement,
+            start,
+            goal,
+            g: Grid::filled(width, height, UNREACHED),
+            rhs: Grid::filled(width, height, UNREACHED),
+            queue: BinaryHeap::new(),
+            compact_at: MIN_COMPACT_AT,
+            stats: SearchStats::default(),
+        };
+        planner.rhs[start] = 0;
+        planner.push(start);
+        Ok(planner)
+    }
+
+    pub fn map(&self) -> &Grid<u8> {
+        &self.map
+    }
+
+    /// set_risk changes the risk of a position, to be taken into account by the next
+    /// `route`. It fails if the position is outside the map.
+    pub fn set_risk(&mut self, pos: Position, risk: u8) -> Result<()> {
+        let current = self
+            .map
+            .get_mut(pos)
+            .ok_or_else(|| anyhow::anyhow!("{:?} is outside the map", pos))?;
+        if *current == risk {
+            return Ok(());
+        }
+        *current = risk;
+        // Only the moves to the position changed risk.
+        self.update(pos);
+        Ok(())
+    }
+
+    /// route returns the lowest risk route from start to goal, or None if there is none.
+    /// The stats count the work done since the last route.
+    pub fn route(&mut self) -> Option<Route> {
+        self.search();
+        let stats = std::mem::take(&mut self.stats);
+        let risk = self.g[self.goal];
+        if risk == UNREACHED {
+            return None;
+        }
+        Some(Route {
+            risk,
+            path: self.get_path(),
+            stats,
+        })
+    }
+
+    /// heuristic is a lower bound of the total risk from `pos` to the goal. It assumes the
+    /// lowest possible risk of 1 for every position, so that it holds whatever risks
+    /// change.
+    fn heuristic(&self, pos: Position) -> Risk {
+        let d = Point2::from(self.goal) - Point2::from(pos);
+        let distance = (d.x.unsigned_abs(), d.y.unsigned_abs());
+        self.movement
+            .min_steps(distance)
+            .saturating_mul(self.movement.min_cost())
+    }
+
+    fn key(&self, pos: Position) -> Key {
+        let risk = std::cmp::min(self.g[pos], self.rhs[pos]);
+        (risk.saturating_add(self.heuristic(pos)), risk)
+    }
+
+    fn push(&mut self, pos: Position) {
+        self.queue.push(Reverse((self.key(pos), pos)));
+        self.stats.pushes += 1;
+        if self.queue.len() >= self.compact_at {
+            self.compact();
+        }
+    }
+
+    /// is_current returns whether a queue entry is of an inconsistent position and its
+    /// current key.
+    fn is_current(&self, key: Key, pos: Position) -> bool {
+        self.g[pos] != self.rhs[pos] && key == self.key(pos)
+    }
+
+    /// top returns the key of the first inconsistent position in the queue, dropping the
+    /// entries that are out of date.
+    fn top(&mut self) -> Option<Key> {
+        while let Some(&Reverse((key, pos))) = self.queue.peek() {
+            if self.is_current(key, pos) {
+                return Some(key);
+            }
+            self.queue.pop();
+        }
+        None
+    }
+
+    /// compact rebuilds the queue from the entries that are current, once each. Every
+    /// inconsistent position was pushed with its current key, so none are lost.
+    fn compact(&mut self) {
+        let mut entries = std::mem::take(&mut self.queue).into_vec();
+        entries.retain(|&Reverse((key, pos))| self.is_current(key, pos));
+        entries.sort_unstable();
+        entries.dedup();
+        self.queue = BinaryHeap::from(entries);
+        self.compact_at = std::cmp::max(2 * self.queue.len(), MIN_COMPACT_AT);
+    }
+
+    /// cost returns the risk of making a move to `to`, None if it can't be entered.
+    fn cost(&self, m: &Move, to: Position) -> Option<Risk> {
+        match self.map[to] {
+            0 => None,
+            risk => Some(risk as Risk * m.cost),
+        }
+    }
+
+    /// predecessors returns the positions `pos` can be moved to from, with the move.
+    fn predecessors(&self, pos: Position) -> impl Iterator<Item = (Position, &Move)> + '_ {
+        self.movement.moves().iter().filter_map(move |m| {
+            let from = pos.offset((-m.offset.0, -m.offset.1))?;
+            Some((from, m)).filter(|_| self.map.contains(from))
+        })
+    }
+
+    fn successors(&self, pos: Position) -> impl Iterator<Item = Position> + '_ {
+        self.movement
+            .moves()
+            .iter()
+            .filter_map(move |m| pos.offset(m.offset))
+            .filter(move |&to| self.map.contains(to))
+    }
+
+    /// update recomputes the `rhs` of a position, queueing it if it became inconsistent.
+    fn update(&mut self, pos: Position) {
+        if pos != self.start {
+            let rhs = self
+                .predecessors(pos)
+                .filter_map(|(from, m)| Some(self.g[from].saturating_add(self.cost(m, pos)?)))
+                .min()
+                .unwrap_or(UNREACHED);
+            self.rhs[pos] = rhs;
+        }
+        if self.g[pos] != self.rhs[pos] {
+            self.push(pos);
+        }
+    }
+
+    /// search makes positions consistent, lowest key first, until the risk to the goal is
+    /// known.
+    fn search(&mut self) {
+        while let Some(key) = self.top() {
+            if key >= self.key(self.goal) && self.g[self.goal] == self.rhs[self.goal] {
+                break;
+            }
+            let Reverse((_, pos)) = self.queue.pop().unwrap();
+            self.stats.expanded += 1;
+            if self.g[pos] > self.rhs[pos] {
+                self.g[pos] = self.rhs[pos];
+            } else {
+                self.g[pos] = UNREACHED;
+                self.update(pos);
+            }
+            let successors = self.successors(pos).collect::<Vec<_>>();
+            for next in successors {
+                self.update(next);
+            }
+        }
+    }
+
+    /// get_path follows the predecessors on a lowest risk path back from the goal.
+    fn get_path(&self) -> Vec<Step> {
+        let mut steps = vec![];
+        let mut pos = self.goal;
+        while pos != self.start {
+            let (from, risk) = self
+                .predecessors(pos)
+                .filter_map(|(from, m)| Some((from, self.cost(m, pos)?)))
+                .find(|&(from, risk)| self.g[from].saturating_add(risk) == self.g[pos])
+                .expect("a predecessor on the lowest risk path");
+            steps.push(Step {
+                pos,
+                risk,
+                total: self.g[pos],
+            });
+            pos = from;
+        }
+        steps.push(Step {
+            pos,
+            risk: 0,
+            total: 0,
+        });
+        steps.reverse();
+        steps
+    }
+}
+
+#[test]
+fn test_replanner() {
+    use super::search::{Algorithm, PathFinder};
+    use super::{add_tiles, Solver};
+    use crate::advent::Solution;
+
+    let map = Solver
+        .parse(
+            "1163751742\n1381373672\n2136511328\n3694931569\n7463417111\n\
+             1319128137\n1359912421\n3125421639\n1293138521\n2311944581\n"
+                .as_bytes(),
+        )
+        .unwrap();
+    let map = add_tiles(&map);
+    let goal = map.max();
+    let mut planner =
+        Replanner::new(map.clone(), Movement::default(), Position(0, 0), goal).unwrap();
+    let first = planner.route().unwrap();
+    assert_eq!(first.risk, 315);
+
+    // A change off the route barely needs searching, one on it far less than a full search.
+    planner.set_risk(Position(49, 0), 1).unwrap();
+    assert_eq!(planner.route().unwrap().stats.expanded, 1);
+    let on_route = first.path[first.path.len() / 2].pos;
+    planner.set_risk(on_route, 9).unwrap();
+    let route = planner.route().unwrap();
+    let fresh = PathFinder::new(planner.map().clone())
+        .search(Position(0, 0), goal, Algorithm::Dijkstra)
+        .unwrap();
+    assert_eq!(route.risk, fresh.risk);
+    assert!(route.stats.expanded * 5 < fresh.stats.expanded);
+
+    // Changes of all kinds, checked against searching from scratch.
+    let mut seed = 15u64;
+    for i in 0..200 {
+        seed = seed
+            .wrapping_mul(6364136223846793005)
+            .wrapping_add(1442695040888963407);
+        let pos = Position((seed >> 33) as usize % 50, (seed >> 17) as usize % 50);
+        let risk = ((seed >> 50) % 10) as u8;
+        planner.set_risk(pos, risk).unwrap();
+        if i % 10 != 0 {
+            continue;
+        }
+        let route = planner.route();
+        let fresh =
+            PathFinder::new(planner.map().clone()).search(Position(0, 0), goal, Algorithm::AStar);
+        assert_eq!(route.as_ref().map(|r| r.risk), fresh.map(|r| r.risk));
+        if let Some(route) = route {
+            for pair in route.path.windows(2) {
+                assert!(planner
+                    .map()
+                    .neighbours4(pair[0].pos)
+                    .any(|pos| pos == pair[1].pos));
+                assert_eq!(pair[1].risk, planner.map()[pair[1].pos] as Risk);
+                assert_eq!(pair[1].total, pair[0].total + pair[1].risk);
+            }
+            assert_eq!(route.path.last().unwrap().total, route.risk);
+        }
+    }
+
+    // Walling off the goal leaves no route, until a way is opened again.
+    let mut planner = Replanner::new(
+        Grid::filled(3, 3, 1),
+        Movement::default(),
+        Position(0, 0),
+        Position(2, 2),
+    )
+    .unwrap();
+    planner.set_risk(Position(1, 2), 0).unwrap();
+    planner.set_risk(Position(2, 1), 0).unwrap();
+    assert!(planner.route().is_none());
+    planner.set_risk(Position(2, 1), 5).unwrap();
+    assert_eq!(planner.route().unwrap().risk, 8);
+    assert!(planner.set_risk(Position(3, 0), 1).is_err());
+
+    // There is no replanning from or to positions outside the map.
+    let err = Replanner::new(
+        Grid::filled(3, 3, 1),
+        Movement::default(),
+        Position(0, 0),
+        Position(0, 3),
+    )
+    .err()
+    .unwrap();
+    assert_eq!(err.to_string(), "goal Position(0, 3) is outside the map");
+    assert!(Replanner::new(map, Movement::default(), Position(50, 0), goal).is_err());
+
+    // Changing a position never searched leaves out of date entries in the queue that
+    // are never popped, until it is compacted.
+    let mut planner = Replanner::new(
+        Grid::filled(50, 50, 1),
+        Movement::default(),
+        Position(0, 0),
+        Position(2, 2),
+    )
+    .unwrap();
+    for i in 0..1000 {
+        planner.set_risk(Position(3, 0), [1, 9][i % 2]).unwrap();
+        assert_eq!(planner.route().unwrap().risk, 4);
+    }
+    assert!(planner.queue.len() < MIN_COMPACT_AT);
+}
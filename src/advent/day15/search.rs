@@ -0,0 +1,430 @@
+//! Lowest risk paths through risk maps.
+
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap};
+
+use anyhow::Result;
+
+use super::field::{DistanceField, NO_MOVE, UNREACHED};
+use super::map::RiskMap;
+use super::movement::Movement;
+use super::Risk;
+use crate::advent::geometry::Point2;
+use crate::advent::grid::{Grid, Position};
+
+/// Algorithm is the search algorithm used by a PathFinder.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Algorithm {
+    Dijkstra,
+    /// AStar guides the search towards the goal, estimating the remaining risk as the
+    /// fewest moves to the goal times the lowest risk of the map and the cheapest move.
+    AStar,
+}
+
+/// SearchStats counts the work done by a search.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct SearchStats {
+    /// expanded is the number of positions whose neighbours were visited.
+    pub expanded: usize,
+    /// pushes is the number of positions pushed onto the priority queue.
+    pub pushes: usize,
+}
+
+/// Step is a position along a route.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Step {
+    pub pos: Position,
+    /// risk is the risk of moving to the position, its risk times the cost of the move, 0
+    /// for the start position.
+    pub risk: Risk,
+    /// total is the total risk of the route up to and including this step.
+    pub total: Risk,
+}
+
+/// Route is the result of a search.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Route {
+    /// risk is the total risk of the path, excluding the start position.
+    pub risk: Risk,
+    /// path is the steps from start to goal, both included.
+    pub path: Vec<Step>,
+    pub stats: SearchStats,
+}
+
+impl Route {
+    /// positions returns the positions of the path, from start to goal.
+    pub fn positions(&self) -> impl Iterator<Item = Position> + '_ {
+        self.path.iter().map(|step| step.pos)
+    }
+}
+
+/// PathFinder finds the lowest total risk path through a map. Positions with a risk of 0
+/// can't be moved to.
+pub struct PathFinder<M = Grid<u8>> {
+    map: M,
+    /// min_risk is the lowest risk of any position, the least a step can cost.
+    min_risk: Risk,
+    movement: Movement,
+    /// blocked marks the positions that can't be moved to, in addition to those with a
+    /// risk of 0.
+    blocked: Option<Grid<bool>>,
+}
+
+impl<M: RiskMap> PathFinder<M> {
+    /// new returns a path finder moving right, down, left and up.
+    pub fn new(map: M) -> Self {
+        let min_risk = map.min_risk() as Risk;
+        Self {
+            map,
+            min_risk,
+            movement: Movement::default(),
+            blocked: None,
+        }
+    }
+
+    pub fn with_movement(mut self, movement: Movement) -> Self {
+        self.movement = movement;
+        self
+    }
+
+    /// with_blocked blocks the positions that are true in `blocked`, failing if it isn't
+    /// the size of the map.
+    pub fn with_blocked(mut self, blocked: Grid<bool>) -> Result<Self> {
+        let size = (blocked.width(), blocked.height());
+        let map_size = (self.map.width(), self.map.height());
+        if size != map_size {
+            return Err(anyhow::anyhow!(
+                "blocked positions are {}x{}, expected the {}x{} of the map",
+                size.0,
+                size.1,
+                map_size.0,
+                map_size.1
+            ));
+        }
+        self.blocked = Some(blocked);
+        Ok(self)
+    }
+
+    /// is_open returns whether `pos` is in the map and can be moved to.
+    pub fn is_open(&self, pos: Position) -> bool {
+        self.map.contains(pos)
+            && self.map.risk(pos) > 0
+            && self.blocked.as_ref().map(|blocked| blocked[pos]) != Some(true)
+    }
+
+    /// adjacents returns the risk of moving to every position reachable from `pos` in a
+    /// single move, the position and the index of the move.
+    fn adjacents(&self, pos: Position) -> impl Iterator<Item = (Risk, Position, u8)> + '_ {
+        self.movement
+            .moves()
+            .iter()
+            .enumerate()
+            .filter_map(move |(i, m)| Some((pos.offset(m.offset)?, m, i as u8)))
+            .filter(move |&(next, _, _)| self.is_open(next))
+            .map(move |(next, m, i)| (self.map.risk(next) as Risk * m.cost, next, i))
+    }
+
+    /// dijkstras returns the lowest total risk from start to goal and the path taken.
+    pub fn dijkstras(&self, start: Position, goal: Position) -> Option<(Risk, Vec<Position>)> {
+        self.search(start, goal, Algorithm::Dijkstra)
+            .map(|route| (route.risk, route.positions().collect()))
+    }
+
+    /// heuristic is a lower bound of the total risk from `pos` to `goal`.
+    fn heuristic(&self, algorithm: Algorithm, pos: Position, goal: Position) -> Risk {
+        match algorithm {
+            Algorithm::Dijkstra => 0,
+            Algorithm::AStar => {
+                let d = Point2::from(goal) - Point2::from(pos);
+                let distance = (d.x.unsigned_abs(), d.y.unsigned_abs());
+                self.movement
+                    .min_steps(distance)
+                    .saturating_mul(self.min_risk)
+                    .saturating_mul(self.movement.min_cost())
+            }
+        }
+    }
+
+    /// search returns the lowest risk route from start to goal, using the given algorithm.
+    /// There is none if either is outside the map.
+    pub fn search(&self, start: Position, goal: Position, algorithm: Algorithm) -> Option<Route> {
+        self.search_with(start, goal, algorithm, |_, _| true)
+    }
+
+    /// search_with searches like `search`, only making the moves `allowed` returns true
+    /// for, given the position moved from and to.
+    pub(super) fn search_with(
+        &self,
+        start: Position,
+        goal: Position,
+        algorithm: Algorithm,
+        allowed: impl Fn(Position, Position) -> bool,
+    ) -> Option<Route> {
+        let field = self.explore(&[start], Some(goal), algorithm, allowed)?;
+        Some(Route {
+            risk: field.risk(goal)?,
+            path: field.path(goal)?,
+            stats: field.stats,
+        })
+    }
+
+    /// explore finds the lowest total risk from the nearest source to every position, or
+    /// until the lowest total risk to `goal` is known. It returns None if a source or the
+    /// goal is outside the map, or the goal can't be moved to. Positions with a total risk
+    /// of UNREACHED or more are never reached.
+    pub(super) fn explore(
+        &self,
+        sources: &[Position],
+        goal: Option<Position>,
+        algorithm: Algorithm,
+        allowed: impl Fn(Position, Position) -> bool,
+    ) -> Option<DistanceField> {
+        let outside = |pos| !self.map.contains(pos);
+        if sources.iter().copied().any(outside) || goal.map(outside) == Some(true) {
+            return None;
+        }
+        // Without this the search would explore everything reachable before giving up.
+        if let Some(goal) = goal {
+            if !self.is_open(goal) && !sources.contains(&goal) {
+                return None;
+            }
+        }
+        let mut stats = SearchStats::default();
+        let heuristic = |pos| goal.map_or(0, |goal| self.heuristic(algorithm, pos, goal));
+
+        // `reached` tracks the lowest known total risk to move from a source to every
+        // position reached so far, and the move it was reached with on that path. Only
+        // reached positions are stored, so that a search towards a goal on a large map
+        // uses memory for the positions it visits rather than the whole map.
+        let mut reached: HashMap<Position, (Risk, u8)> = HashMap::new();
+        let dist = |reached: &HashMap<Position, (Risk, u8)>, pos| {
+            reached.get(&pos).map_or(UNREACHED, |&(risk, _)| risk)
+        };
+
+        // Create a min-heap (reverse estimated risk) to yield the most promising positions
+        // first. With Dijkstra the estimate is the known risk to get to the position.
+        let mut queue: BinaryHeap<(Reverse<Risk>, Reverse<Risk>, Position)> = BinaryHeap::new();
+        // Start searching paths from the sources with an inital total risk for the path of 0.
+        for &source in sources {
+            reached.insert(source, (0, NO_MOVE)); // Distance to a source is 0.
+            queue.push((Reverse(heuristic(source)), Reverse(0), source));
+            stats.pushes += 1;
+        }
+
+        // Try lowest estimated total risk positions first
+        while let Some((_, Reverse(risk), position)) = queue.pop() {
+            if Some(position) == goal {
+                break;
+            }
+
+            // Stop search and reject path if we got to this position in a roundabout way
+            if risk > dist(&reached, position) {
+                continue;
+            }
+            stats.expanded += 1;
+
+            // Check if it's viable to continue along this path
+            for neighbor in self.adjacents(position) {
+                if !allowed(position, neighbor.1) {
+                    continue;
+                }
+                let next_cost = risk.saturating_add(neighbor.0);
+                let (next_pos, next_move) = (neighbor.1, neighbor.2);
+
+                // If there's no better, known path to get to the position, continue.
+                if next_cost < dist(&reached, next_pos) {
+                    // Mark the new, lowest known risk to get to the new position.
+                    reached.insert(next_pos, (next_cost, next_move));
+                    // Continue traversing the path through the new position.
+                    let estimate = next_cost.saturating_add(heuristic(next_pos));
+                    queue.push((Reverse(estimate), Reverse(next_cost), next_pos));
+                    stats.pushes += 1;
+                }
+            }
+        }
+        let size = (self.map.width(), self.map.height());
+        Some(DistanceField::new(
+            size,
+            reached,
+            self.movement.clone(),
+            stats,
+        ))
+    }
+}
+
+#[test]
+fn test_search() {
+    use super::map::TiledMap;
+    use super::{add_tiles, Solver};
+    use crate::advent::Solution;
+
+    let map = Solver
+        .parse(
+            "1163751742\n1381373672\n2136511328\n3694931569\n7463417111\n\
+             1319128137\n1359912421\n3125421639\n1293138521\n2311944581\n"
+                .as_bytes(),
+        )
+        .unwrap();
+    let finder = PathFinder::new(add_tiles(&map));
+    let goal = Position(49, 49);
+    for algorithm in [Algorithm::Dijkstra, Algorithm::AStar] {
+        let route = finder.search(Position(0, 0), goal, algorithm).unwrap();
+        assert_eq!(route.risk, 315);
+    }
+
+    // A* skips the positions that can't be on a path to a goal closer than the far corner.
+    for goal in [Position(10, 10), Position(40, 5)] {
+        let dijkstra = finder
+            .search(Position(0, 0), goal, Algorithm::Dijkstra)
+            .unwrap();
+        let astar = finder
+            .search(Position(0, 0), goal, Algorithm::AStar)
+            .unwrap();
+        assert_eq!(astar.risk, dijkstra.risk);
+        assert!(astar.stats.expanded < dijkstra.stats.expanded);
+        assert!(astar.stats.pushes < dijkstra.stats.pushes);
+    }
+
+    // Searching towards a goal only keeps the positions it reaches, even on a map of a
+    // trillion positions.
+    let huge = PathFinder::new(TiledMap::new(map, 100_000, 100_000).unwrap());
+    let goal = Position(40, 30);
+    let route = huge.search(Position(0, 0), goal, Algorithm::AStar).unwrap();
+    let expected = finder
+        .search(Position(0, 0), goal, Algorithm::AStar)
+        .unwrap();
+    assert_eq!(route.risk, expected.risk);
+    assert_eq!(route.stats.expanded, expected.stats.expanded);
+
+    // The path is exact for any start and goal, and adds up to the route's risk.
+    let route = finder
+        .search(Position(7, 3), Position(2, 8), Algorithm::AStar)
+        .unwrap();
+    let first = route.path.first().unwrap();
+    let last = route.path.last().unwrap();
+    assert_eq!((first.pos, first.risk, first.total), (Position(7, 3), 0, 0));
+    assert_eq!((last.pos, last.total), (Position(2, 8), route.risk));
+    for pair in route.path.windows(2) {
+        assert!(finder
+            .map
+            .neighbours4(pair[0].pos)
+            .any(|pos| pos == pair[1].pos));
+        assert_eq!(pair[1].risk, finder.map.risk(pair[1].pos) as Risk);
+        assert_eq!(pair[1].total, pair[0].total + pair[1].risk);
+    }
+
+    let small = PathFinder::new(Grid::new(vec![1, 9, 1, 1, 1, 1], 3));
+    let route = small
+        .search(Position(0, 0), Position(2, 0), Algorithm::Dijkstra)
+        .unwrap();
+    assert_eq!(route.risk, 4);
+    assert_eq!(
+        route.positions().collect::<Vec<_>>(),
+        [
+            Position(0, 0),
+            Position(0, 1),
+            Position(1, 1),
+            Position(2, 1),
+            Position(2, 0)
+        ]
+    );
+}
+
+#[test]
+fn test_movement_rules() {
+    use super::map::TiledMap;
+    use super::movement::{Move, KNIGHT_MOVES};
+
+    let map = Grid::parse_digits("1999\n9199\n9919\n9991\n".as_bytes()).unwrap();
+    let goal = map.max();
+    let four = PathFinder::new(map.clone());
+    assert_eq!(four.dijkstras(Position(0, 0), goal).unwrap().0, 30);
+
+    // Diagonal moves cost twice the risk of the position.
+    for algorithm in [Algorithm::Dijkstra, Algorithm::AStar] {
+        let eight = PathFinder::new(map.clone()).with_movement(Movement::eight_way(2).unwrap());
+        let route = eight.search(Position(0, 0), goal, algorithm).unwrap();
+        assert_eq!(route.risk, 6);
+        assert_eq!(
+            route.path.iter().map(|step| step.risk).collect::<Vec<_>>(),
+            [0, 2, 2, 2]
+        );
+    }
+
+    let knight =
+        PathFinder::new(map.clone()).with_movement(Movement::custom(&KNIGHT_MOVES).unwrap());
+    let route = knight
+        .search(Position(0, 0), Position(1, 2), Algorithm::AStar)
+        .unwrap();
+    assert_eq!(
+        route.positions().collect::<Vec<_>>(),
+        [Position(0, 0), Position(1, 2)]
+    );
+    assert!(knight
+        .search(Position(0, 0), Position(1, 1), Algorithm::AStar)
+        .is_some());
+
+    // Zero risk and blocked positions are walls.
+    let walled = Grid::parse_digits("1101\n1101\n1111\n".as_bytes()).unwrap();
+    let finder = PathFinder::new(walled.clone());
+    let route = finder
+        .search(Position(0, 0), Position(3, 0), Algorithm::AStar)
+        .unwrap();
+    assert_eq!(route.risk, 7);
+    let mut blocked = Grid::filled(4, 3, false);
+    blocked[Position(2, 2)] = true;
+    assert!(finder.with_blocked(Grid::filled(3, 4, false)).is_err());
+    let finder = PathFinder::new(walled.clone())
+        .with_blocked(blocked)
+        .unwrap();
+    assert!(!finder.is_open(Position(2, 2)));
+    assert!(finder
+        .search(Position(0, 0), Position(3, 0), Algorithm::Dijkstra)
+        .is_none());
+
+    // There are no routes from or to positions outside the map.
+    let finder = PathFinder::new(walled.clone());
+    for (start, goal) in [
+        (Position(4, 0), Position(0, 0)),
+        (Position(0, 0), Position(0, 3)),
+    ] {
+        assert!(finder.search(start, goal, Algorithm::AStar).is_none());
+        assert!(finder.k_shortest_paths(start, goal, 2).is_empty());
+    }
+    assert!(finder
+        .distance_field(&[Position(0, 0), Position(9, 9)])
+        .is_none());
+    // Nor to walls, which are given up on without exploring the map.
+    let huge = TiledMap::new(walled.clone(), 100_000, 100_000).unwrap();
+    let finder = PathFinder::new(huge);
+    assert!(finder
+        .search(Position(0, 0), Position(2, 1), Algorithm::AStar)
+        .is_none());
+    let route = finder
+        .search(Position(2, 0), Position(2, 0), Algorithm::AStar)
+        .unwrap();
+    assert_eq!(route.risk, 0);
+
+    // Total risks too high for a Risk are never reached, rather than overflowing.
+    let map = Grid::new(vec![u8::MAX; 9], 3);
+    let eight = Movement::eight_way(Movement::MAX_COST).unwrap();
+    let finder = PathFinder::new(map.clone()).with_movement(eight);
+    for algorithm in [Algorithm::Dijkstra, Algorithm::AStar] {
+        let route = finder
+            .search(Position(0, 0), Position(2, 2), algorithm)
+            .unwrap();
+        assert_eq!(route.risk, 4 * u8::MAX as Risk);
+    }
+    let diagonal = Movement::new(vec![Move {
+        offset: (1, 1),
+        cost: Movement::MAX_COST,
+    }])
+    .unwrap();
+    let finder = PathFinder::new(map).with_movement(diagonal);
+    let field = finder.distance_field(&[Position(0, 0)]).unwrap();
+    assert_eq!(
+        field.risk(Position(1, 1)),
+        Some(u8::MAX as Risk * Movement::MAX_COST)
+    );
+    assert_eq!(field.risk(Position(2, 2)), None);
+}
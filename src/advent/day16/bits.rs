@@ -0,0 +1,220 @@
+//! Reading and writing transmissions one bit at a time, most significant bit first.
+
+use std::io::{self, BufRead, BufReader, Read};
+
+/// BitReader reads bits from a stream of bytes, keeping count of the bits read. The stream
+/// is buffered.
+pub struct BitReader<R> {
+    inner: BufReader<R>,
+    /// byte is the byte being read, its unread bits at the bottom.
+    byte: u8,
+    /// bits_left is the number of unread bits of `byte`.
+    bits_left: usize,
+    offset: usize,
+}
+
+impl<R: Read> BitReader<R> {
+    pub fn new(inner: R) -> Self {
+        Self {
+            inner: BufReader::new(inner),
+            byte: 0,
+            bits_left: 0,
+            offset: 0,
+        }
+    }
+
+    /// offset is the number of bits read so far.
+    pub fn offset(&self) -> usize {
+        self.offset
+    }
+
+    /// read_bits reads `n` bits, at most 64, as a number.
+    pub fn read_bits(&mut self, n: usize) -> io::Result<u64> {
+        assert!(n <= 64, "can not read {} bits at once", n);
+        let mut value = 0u64;
+        let mut remaining = n;
+        while remaining > 0 {
+            if self.bits_left == 0 {
+                let mut buf = [0u8];
+                self.inner.read_exact(&mut buf)?;
+                self.byte = buf[0];
+                self.bits_left = 8;
+            }
+            let take = std::cmp::min(remaining, self.bits_left);
+            let bits = (self.byte as u64 >> (self.bits_left - take)) & ((1 << take) - 1);
+            value = value << take | bits;
+            self.bits_left -= take;
+            self.offset += take;
+            remaining -= take;
+        }
+        Ok(value)
+    }
+
+    /// read_bit reads a single bit.
+    pub fn read_bit(&mut self) -> io::Result<bool> {
+        Ok(self.read_bits(1)? == 1)
+    }
+
+    /// padding reads the rest of the current byte, returning the unread bits.
+    pub fn padding(&mut self) -> io::Result<u64> {
+        self.read_bits(self.bits_left)
+    }
+
+    /// into_inner returns the buffered stream, holding the bytes read ahead of the bits.
+    pub fn into_inner(self) -> BufReader<R> {
+        self.inner
+    }
+}
+
+/// HexReader reads the bytes of a hexadecimal stream, such as `D2FE28`. Whitespace is
+/// skipped.
+pub struct HexReader<R> {
+    inner: BufReader<R>,
+    /// error is an error after the bytes of the last read, returned by the next one.
+    error: Option<io::Error>,
+}
+
+impl<R: Read> HexReader<R> {
+    pub fn new(inner: R) -> Self {
+        Self {
+            inner: BufReader::new(inner),
+            error: None,
+        }
+    }
+
+    /// next_byte returns the next byte, or None at the end of the stream.
+    fn next_byte(&mut self) -> io::Result<Option<u8>> {
+        let high = match self.next_digit()? {
+            Some(digit) => digit,
+            None => return Ok(None),
+        };
+        let low = self.next_digit()?.ok_or_else(|| {
+            io::Error::new(io::ErrorKind::InvalidData, "odd number of hex digits")
+        })?;
+        Ok(Some(high << 4 | low))
+    }
+
+    /// next_digit returns the value of the next hex digit, or None at the end of the stream.
+    fn next_digit(&mut self) -> io::Result<Option<u8>> {
+        loop {
+            let c = match self.inner.fill_buf()?.first() {
+                Some(&c) => c,
+                None => return Ok(None),
+            };
+            self.inner.consume(1);
+            if c.is_ascii_whitespace() {
+                continue;
+            }
+            return match (c as char).to_digit(16) {
+                Some(digit) => Ok(Some(digit as u8)),
+                None => Err(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!("bad hex digit '{}'", (c as char).escape_default()),
+                )),
+            };
+        }
+    }
+}
+
+impl<R: Read> Read for HexReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if let Some(err) = self.error.take() {
+            return Err(err);
+        }
+        for (i, byte) in buf.iter_mut().enumerate() {
+            match self.next_byte() {
+                Ok(Some(b)) => *byte = b,
+                Ok(None) => return Ok(i),
+                // The bytes before the error are returned first.
+                Err(err) if i > 0 => {
+                    self.error = Some(err);
+                    return Ok(i);
+                }
+                Err(err) => return Err(err),
+            }
+        }
+        Ok(buf.len())
+    }
+}
+
+/// BitWriter packs bits into bytes.
+#[derive(Default)]
+pub struct BitWriter {
+    bytes: Vec<u8>,
+    /// len is the number of bits written.
+    len: usize,
+}
+
+impl BitWriter {
+    /// len is the number of bits written.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// write_bits writes the lowest `n` bits of `value`.
+    pub fn write_bits(&mut self, value: u64, n: usize) {
+        for i in (0..n).rev() {
+            self.write_bit((value >> i) as u8 & 1);
+        }
+    }
+
+    fn write_bit(&mut self, bit: u8) {
+        let offset = self.len % 8;
+        if offset == 0 {
+            self.bytes.push(0);
+        }
+        *self.bytes.last_mut().unwrap() |= bit << (7 - offset);
+        self.len += 1;
+    }
+
+    /// append writes all bits written to `other`.
+    pub fn append(&mut self, other: &BitWriter) {
+        for i in 0..other.len {
+            self.write_bit((other.bytes[i / 8] >> (7 - i % 8)) & 1);
+        }
+    }
+
+    /// into_bytes returns the bytes written, padding the last byte with zeros.
+    pub fn into_bytes(self) -> Vec<u8> {
+        self.bytes
+    }
+}
+
+#[test]
+fn test_bit_reader() {
+    let mut r = BitReader::new(HexReader::new("D2FE28\n".as_bytes()));
+    assert_eq!(r.read_bits(3).unwrap(), 6);
+    assert_eq!(r.read_bits(3).unwrap(), 4);
+    assert!(r.read_bit().unwrap());
+    assert_eq!(r.read_bits(4).unwrap(), 0b0111);
+    assert_eq!(r.offset(), 11);
+    assert_eq!(r.read_bits(10).unwrap(), 0b1111000101);
+    assert_eq!(r.padding().unwrap(), 0b000);
+    assert_eq!(r.offset(), 24);
+    let err = r.read_bits(1).unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::UnexpectedEof);
+
+    let bytes = [0x12, 0x34, 0x56, 0x78, 0x9a, 0xbc, 0xde, 0xf0, 0x0f];
+    let mut r = BitReader::new(&bytes[..]);
+    assert_eq!(r.read_bits(4).unwrap(), 1);
+    assert_eq!(r.read_bits(64).unwrap(), 0x23456789abcdef00);
+    assert_eq!(r.read_bits(0).unwrap(), 0);
+    assert_eq!(r.read_bits(4).unwrap(), 0xf);
+
+    let mut bytes = vec![];
+    let err = HexReader::new("ABC".as_bytes()).read_to_end(&mut bytes);
+    assert_eq!(err.unwrap_err().to_string(), "odd number of hex digits");
+    let err = HexReader::new("AX".as_bytes()).read_to_end(&mut bytes);
+    assert_eq!(err.unwrap_err().to_string(), "bad hex digit 'X'");
+
+    // The bits before a bad digit are read before the error, although the stream is
+    // read ahead.
+    let mut r = BitReader::new(HexReader::new("D2FE28X".as_bytes()));
+    assert_eq!(r.read_bits(24).unwrap(), 0xD2FE28);
+    let err = r.read_bits(8).unwrap_err();
+    assert_eq!(err.to_string(), "bad hex digit 'X'");
+}
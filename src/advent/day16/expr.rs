@@ -0,0 +1,427 @@
+//! A small infix notation for the expressions BITS transmissions compute, such as
+//! `max(3, 4 * 5) == 20`.
+//!
+//! `+` and `*` are sum and product packets, `>`, `<` and `==` the comparison packets and
+//! `min(..)` and `max(..)` the minimum and maximum packets. Every operation can also be
+//! written as a function, `sum`, `product`, `min`, `max`, `gt`, `lt` or `eq`, which is how
+//! packets that have no infix form, such as a sum of a single value, are decompiled.
+//! Comparisons bind loosest and cannot be chained, `*` binds tightest.
+
+use std::fmt::Write;
+
+use anyhow::Result;
+
+use super::{Op, Packet, Version, MAX_DEPTH};
+use crate::advent::input::ParseError;
+
+/// VERSION is the version of the packets of compiled expressions.
+const VERSION: Version = 0;
+
+/// compile parses an expression into the packet computing it.
+pub fn compile(src: &str) -> Result<Packet, ParseError> {
+    let mut parser = Parser {
+        src,
+        tokens: tokenize(src)?,
+        next: 0,
+        depth: 0,
+    };
+    let packet = parser.comparison()?;
+    match parser.peek() {
+        None => Ok(packet),
+        Some(_) => Err(parser.error("expected the end of the expression")),
+    }
+}
+
+/// compile_hex compiles an expression into the hexadecimal transmission of its packet.
+pub fn compile_hex(src: &str) -> Result<String> {
+    compile(src)?.to_hex()
+}
+
+/// decompile writes the expression a packet computes, ignoring packet versions.
+pub fn decompile(packet: &Packet) -> String {
+    let mut s = String::new();
+    let mut stack = vec![Piece::Expr(packet)];
+    while let Some(piece) = stack.pop() {
+        match piece {
+            Piece::Expr(packet) => write_expr(&mut s, packet, &mut stack).unwrap(),
+            Piece::Text(text) => s.push_str(text),
+            Piece::Infix(symbol) => write!(s, " {} ", symbol).unwrap(),
+        }
+    }
+    s
+}
+
+/// Piece is a part of an expression that is still to be written.
+enum Piece<'a> {
+    Expr(&'a Packet),
+    Text(&'static str),
+    Infix(&'static str),
+}
+
+/// Precedence of the notations, from loosest to tightest binding.
+const COMPARISON: u8 = 0;
+const SUM: u8 = 1;
+const PRODUCT: u8 = 2;
+const ATOM: u8 = 3;
+
+/// infix returns the infix operator and precedence a packet is written with, or None if it
+/// is written as a literal or a function call.
+fn infix(packet: &Packet) -> Option<(&'static str, u8)> {
+    match packet {
+        Packet::Literal { .. } => None,
+        Packet::Operator { op, children, .. } => match (op, children.len()) {
+            (Op::Sum, 2..) => Some(("+", SUM)),
+            (Op::Product, 2..) => Some(("*", PRODUCT)),
+            (Op::GreaterThan, 2) => Some((">", COMPARISON)),
+            (Op::LessThan, 2) => Some(("<", COMPARISON)),
+            (Op::EqualTo, 2) => Some(("==", COMPARISON)),
+            _ => None,
+        },
+    }
+}
+
+fn precedence(packet: &Packet) -> u8 {
+    infix(packet).map_or(ATOM, |(_, precedence)| precedence)
+}
+
+/// write_expr writes the start of the expression of a packet, pushing the rest of it on
+/// `stack` in reverse, so that the piece to write next is on top.
+fn write_expr<'a>(
+    w: &mut impl Write,
+    packet: &'a Packet,
+    stack: &mut Vec<Piece<'a>>,
+) -> std::fmt::Result {
+    let (op, children) = match packet {
+        Packet::Literal { value, .. } => return write!(w, "{}", value),
+        Packet::Operator { op, children, .. } => (op, children),
+    };
+    match infix(packet) {
+        Some((symbol, precedence)) => {
+            for (i, child) in children.iter().enumerate().rev() {
+                // Parenthesize children binding as loosely, so that the same tree is
+                // compiled back.
+                if self::precedence(child) <= precedence {
+                    stack.extend([Piece::Text(")"), Piece::Expr(child), Piece::Text("(")]);
+                } else {
+                    stack.push(Piece::Expr(child));
+                }
+                if i > 0 {
+                    stack.push(Piece::Infix(symbol));
+                }
+            }
+            Ok(())
+        }
+        None => {
+            write!(w, "{}(", function_name(*op))?;
+            stack.push(Piece::Text(")"));
+            for (i, child) in children.iter().enumerate().rev() {
+                stack.push(Piece::Expr(child));
+                if i > 0 {
+                    stack.push(Piece::Text(", "));
+                }
+            }
+            Ok(())
+        }
+    }
+}
+
+fn function_name(op: Op) -> &'static str {
+    match op {
+        Op::Sum => "sum",
+        Op::Product => "product",
+        Op::Minimum => "min",
+        Op::Maximum => "max",
+        Op::GreaterThan => "gt",
+        Op::LessThan => "lt",
+        Op::EqualTo => "eq",
+    }
+}
+
+fn function_op(name: &str) -> Option<Op> {
+    match name {
+        "sum" => Some(Op::Sum),
+        "product" => Some(Op::Product),
+        "min" => Some(Op::Minimum),
+        "max" => Some(Op::Maximum),
+        "gt" => Some(Op::GreaterThan),
+        "lt" => Some(Op::LessThan),
+        "eq" => Some(Op::EqualTo),
+        _ => None,
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum Token<'a> {
+    Number(&'a str),
+    Name(&'a str),
+    Symbol(&'a str),
+}
+
+impl<'a> Token<'a> {
+    fn text(&self) -> &'a str {
+        match self {
+            Token::Number(s) | Token::Name(s) | Token::Symbol(s) => s,
+        }
+    }
+}
+
+const SYMBOLS: [&str; 8] = ["==", "+", "*", "<", ">", "(", ")", ","];
+
+/// tokenize splits an expression into tokens and their byte offsets.
+fn tokenize(src: &str) -> Result<Vec<(usize, Token<'_>)>, ParseError> {
+    let mut tokens = vec![];
+    let mut rest = src.trim_start();
+    while let Some(c) = rest.chars().next() {
+        let offset = src.len() - rest.len();
+        let len = if c.is_ascii_digit() || c.is_ascii_alphabetic() {
+            let len = rest
+                .find(|c: char| !c.is_ascii_alphanumeric())
+                .unwrap_or(rest.len());
+            tokens.push((
+                offset,
+                match c.is_ascii_digit() {
+                    true => Token::Number(&rest[..len]),
+                    false => Token::Name(&rest[..len]),
+                },
+            ));
+            len
+        } else if let Some(symbol) = SYMBOLS.iter().find(|&s| rest.starts_with(s)) {
+            tokens.push((offset, Token::Symbol(symbol)));
+            symbol.len()
+        } else {
+            let text = &rest[..c.len_utf8()];
+            return Err(error_at(src, offset, text, "unexpected character"));
+        };
+        rest = rest[len..].trim_start();
+    }
+    Ok(tokens)
+}
+
+fn error_at(src: &str, offset: usize, text: &str, reason: &str) -> ParseError {
+    ParseError::new(1, src[..offset].chars().count() + 1, text, reason)
+}
+
+/// Parser is a recursive descent parser over the tokens of an expression.
+struct Parser<'a> {
+    src: &'a str,
+    tokens: Vec<(usize, Token<'a>)>,
+    next: usize,
+    /// depth is the number of parentheses and function calls the parser is in.
+    depth: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn peek(&self) -> Option<&Token<'a>> {
+        self.tokens.get(self.next).map(|(_, token)| token)
+    }
+
+    fn advance(&mut self) -> Option<Token<'a>> {
+        let token = self.tokens.get(self.next).map(|(_, token)| token.clone());
+        self.next += 1;
+        token
+    }
+
+    /// eat consumes the next token if it is `symbol`.
+    fn eat(&mut self, symbol: &str) -> bool {
+        if self.peek() == Some(&Token::Symbol(symbol)) {
+            self.next += 1;
+            return true;
+        }
+        false
+    }
+
+    fn expect(&mut self, symbol: &str) -> Result<(), ParseError> {
+        if self.eat(symbol) {
+            return Ok(());
+        }
+        Err(self.error(&format!("expected '{}'", symbol)))
+    }
+
+    /// error returns an error at the next token.
+    fn error(&self, reason: &str) -> ParseError {
+        self.error_at(self.next, reason)
+    }
+
+    /// error_at returns an error at the token with index `i`, or at the end.
+    fn error_at(&self, i: usize, reason: &str) -> ParseError {
+        match self.tokens.get(i) {
+            Some((offset, token)) => error_at(self.src, *offset, token.text(), reason),
+            None => error_at(self.src, self.src.trim_end().len(), "", reason),
+        }
+    }
+
+    /// nested parses a comparison in parentheses or a function call, at most MAX_DEPTH
+    /// levels deep.
+    fn nested(&mut self) -> Result<Packet, ParseError> {
+        if self.depth == MAX_DEPTH {
+            let reason = format!("expressions nested deeper than {}", MAX_DEPTH);
+            return Err(self.error_at(self.next - 1, &reason));
+        }
+        self.depth += 1;
+        let packet = self.comparison();
+        self.depth -= 1;
+        packet
+    }
+
+    fn comparison(&mut self) -> Result<Packet, ParseError> {
+        let lhs = self.sum()?;
+        let op = match self.peek() {
+            Some(Token::Symbol(">")) => Op::GreaterThan,
+            Some(Token::Symbol("<")) => Op::LessThan,
+            Some(Token::Symbol("==")) => Op::EqualTo,
+            _ => return Ok(lhs),
+        };
+        self.next += 1;
+        let rhs = self.sum()?;
+        Ok(Packet::operator(VERSION, op, vec![lhs, rhs]))
+    }
+
+    fn sum(&mut self) -> Result<Packet, ParseError> {
+        let mut terms = vec![self.product()?];
+        while self.eat("+") {
+            terms.push(self.product()?);
+        }
+        Ok(chain(Op::Sum, terms))
+    }
+
+    fn product(&mut self) -> Result<Packet, ParseError> {
+        let mut factors = vec![self.atom()?];
+        while self.eat("*") {
+            factors.push(self.atom()?);
+        }
+        Ok(chain(Op::Product, factors))
+    }
+
+    fn atom(&mut self) -> Result<Packet, ParseError> {
+        let error = self.error("expected a number, a function or '('");
+        match self.advance() {
+            Some(Token::Number(n)) => {
+                let value = n
+                    .parse()
+                    .map_err(|e| self.error_at(self.next - 1, &format!("{}", e)))?;
+                Ok(Packet::big_literal(VERSION, value))
+            }
+            Some(Token::Name(name)) => {
+                let name_at = self.next - 1;
+                let op =
+                    function_op(name).ok_or_else(|| self.error_at(name_at, "unknown function"))?;
+                self.expect("(")?;
+                let mut args = vec![];
+                if !self.eat(")") {
+                    loop {
+                        args.push(self.nested()?);
+                        if self.eat(")") {
+                            break;
+                        }
+                        self.expect(",")?;
+                    }
+                }
+                if !op.arity().contains(&args.len()) {
+                    let reason = format!("{} takes {}", name, op.describe_arity("operand"));
+                    return Err(self.error_at(name_at, &reason));
+                }
+                Ok(Packet::operator(VERSION, op, args))
+            }
+            Some(Token::Symbol("(")) => {
+                let packet = self.nested()?;
+                self.expect(")")?;
+                Ok(packet)
+            }
+            _ => Err(error),
+        }
+    }
+}
+
+/// chain returns the single operand, or the operation of all operands.
+fn chain(op: Op, mut operands: Vec<Packet>) -> Packet {
+    match operands.len() {
+        1 => operands.pop().unwrap(),
+        _ => Packet::operator(VERSION, op, operands),
+    }
+}
+
+#[test]
+fn test_compile() {
+    let packet = compile("max(3, 4*5) == 20").unwrap();
+    assert_eq!(packet.eval::<u64>(), Ok(1));
+    assert_eq!(decompile(&packet), "max(3, 4 * 5) == 20");
+    let hex = compile_hex("max(3, 4*5) == 20").unwrap();
+    assert_eq!(hex, packet.to_hex().unwrap());
+    let decoded = Packet::decode(
+        &super::get_input_hex(&hex).unwrap(),
+        super::Padding::Lenient,
+    )
+    .unwrap();
+    assert_eq!(decoded, packet);
+
+    for (src, expected, value) in [
+        ("1 + 2 * 3", "1 + 2 * 3", 7),
+        ("(1 + 2) * 3", "(1 + 2) * 3", 9),
+        ("1 + (2 + 3)", "1 + (2 + 3)", 6),
+        ("sum(4)", "sum(4)", 4),
+        ("min(5,2,8) < (1 < 2)", "min(5, 2, 8) < (1 < 2)", 0),
+        ("((7))", "7", 7),
+    ] {
+        let packet = compile(src).unwrap();
+        assert_eq!(decompile(&packet), expected);
+        assert_eq!(compile(expected).unwrap(), packet);
+        assert_eq!(packet.eval::<u64>(), Ok(value));
+    }
+
+    // Any transmission decompiles.
+    let packet = Packet::decode(
+        &super::get_input_hex("9C0141080250320F1802104A08").unwrap(),
+        super::Padding::Lenient,
+    )
+    .unwrap();
+    assert_eq!(decompile(&packet), "1 + 3 == 2 * 2");
+
+    let err = |src| compile(src).unwrap_err().to_string();
+    assert_eq!(
+        err("1 +"),
+        "line 1, column 4: expected a number, a function or '(': ''"
+    );
+    assert_eq!(
+        err("avg(1, 2)"),
+        "line 1, column 1: unknown function: 'avg'"
+    );
+    assert_eq!(
+        err("1 < 2 < 3"),
+        "line 1, column 7: expected the end of the expression: '<'"
+    );
+    assert_eq!(err("max(1 2)"), "line 1, column 7: expected ',': '2'");
+    assert_eq!(
+        err("eq(1, 1, 1)"),
+        "line 1, column 1: eq takes 2 operands: 'eq'"
+    );
+    assert_eq!(
+        err("min()"),
+        "line 1, column 1: min takes at least 1 operand: 'min'"
+    );
+    assert_eq!(err("2 - 1"), "line 1, column 3: unexpected character: '-'");
+    assert_eq!(
+        compile_hex("gt(1)").unwrap_err().to_string(),
+        "line 1, column 1: gt takes 2 operands: 'gt'"
+    );
+
+    // Nesting is limited like decoding, however deep the input.
+    let nested = |n| format!("{}1{}", "(".repeat(n), ")".repeat(n));
+    assert_eq!(compile(&nested(MAX_DEPTH)).unwrap(), Packet::literal(0, 1));
+    assert_eq!(
+        err(&nested(200_000)),
+        format!(
+            "line 1, column {}: expressions nested deeper than {}: '('",
+            MAX_DEPTH + 1,
+            MAX_DEPTH
+        )
+    );
+
+    // Decompiling doesn't recurse, so trees deeper than the limit still decompile.
+    let n = 10 * MAX_DEPTH;
+    let mut packet = Packet::literal(0, 1);
+    for _ in 0..n {
+        packet = Packet::operator(0, Op::Sum, vec![packet]);
+    }
+    let expected = format!("{}1{}", "sum(".repeat(n), ")".repeat(n));
+    assert_eq!(decompile(&packet), expected);
+}
@@ -0,0 +1,779 @@
+use std::fmt::Write;
+use std::io::{self, Read};
+use std::ops::RangeInclusive;
+
+use super::{input, Answer, Solution};
+use anyhow::Result;
+
+pub mod bits;
+pub mod expr;
+pub mod number;
+pub mod trace;
+
+use bits::{BitReader, BitWriter, HexReader};
+use num_bigint::BigUint;
+use number::{number_name, EvalError, Number};
+
+/// Solver solves the day 16 puzzle.
+pub struct Solver;
+
+impl Solution for Solver {
+    const DAY: u8 = 16;
+    const TITLE: &'static str = "Packet Decoder";
+
+    type Input = Packet;
+
+    fn parse(&self, input: impl std::io::Read) -> Result<Self::Input> {
+        let mut r = BitReader::new(HexReader::new(input));
+        Ok(Packet::read_transmission(&mut r, Padding::Lenient)?)
+    }
+
+    fn part1(&self, packet: &Self::Input) -> Result<Answer> {
+        Ok(packet.version_sum().into())
+    }
+
+    fn part2(&self, packet: &Self::Input) -> Result<Answer> {
+        let value = packet.eval::<BigUint>()?;
+        match i128::try_from(&value) {
+            Ok(n) => Ok(Answer::Number(n)),
+            Err(_) => Ok(Answer::Text(value.to_string())),
+        }
+    }
+}
+
+/// get_input_hex decodes a hexadecimal transmission into bytes.
+pub fn get_input_hex(buf: &str) -> Result<Vec<u8>> {
+    input::get_input_hex(buf.as_bytes())
+}
+
+pub type Version = u8;
+
+/// Packet is a decoded BITS packet.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Packet {
+    /// Literal is a packet with type id 4, holding a single number. `groups` is the number
+    /// of 4 bit groups the number is encoded in, which can include leading zero groups.
+    Literal {
+        version: Version,
+        value: BigUint,
+        groups: usize,
+    },
+    /// Operator is a packet applying `op` to its sub-packets.
+    Operator {
+        version: Version,
+        op: Op,
+        length_type: LengthType,
+        children: Vec<Packet>,
+    },
+}
+
+/// Op is the operation of an operator packet, given by its type id.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Op {
+    Sum,
+    Product,
+    Minimum,
+    Maximum,
+    GreaterThan,
+    LessThan,
+    EqualTo,
+}
+
+/// LengthType is how an operator packet gives the extent of its sub-packets.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum LengthType {
+    /// TotalBits (length type id 0) gives the total length in bits of the sub-packets.
+    TotalBits,
+    /// Count (length type id 1) gives the number of sub-packets.
+    Count,
+}
+
+/// LITERAL_TYPE_ID is the type id of literal packets.
+pub const LITERAL_TYPE_ID: u8 = 4;
+
+/// MAX_DEPTH is the most packets on a path from the outermost packet that are compiled,
+/// decoded, encoded or evaluated, so that nesting can't overflow the stack.
+pub const MAX_DEPTH: usize = 256;
+
+impl Op {
+    /// from_type_id returns the operation of an operator packet type id.
+    pub fn from_type_id(type_id: u8) -> Option<Self> {
+        match type_id {
+            0 => Some(Op::Sum),
+            1 => Some(Op::Product),
+            2 => Some(Op::Minimum),
+            3 => Some(Op::Maximum),
+            5 => Some(Op::GreaterThan),
+            6 => Some(Op::LessThan),
+            7 => Some(Op::EqualTo),
+            _ => None,
+        }
+    }
+
+    pub fn type_id(self) -> u8 {
+        match self {
+            Op::Sum => 0,
+            Op::Product => 1,
+            Op::Minimum => 2,
+            Op::Maximum => 3,
+            Op::GreaterThan => 5,
+            Op::LessThan => 6,
+            Op::EqualTo => 7,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Op::Sum => "sum",
+            Op::Product => "product",
+            Op::Minimum => "minimum",
+            Op::Maximum => "maximum",
+            Op::GreaterThan => "greater than",
+            Op::LessThan => "less than",
+            Op::EqualTo => "equal to",
+        }
+    }
+
+    /// arity is the allowed number of sub-packets.
+    pub fn arity(self) -> RangeInclusive<usize> {
+        match self {
+            Op::Sum | Op::Product | Op::Minimum | Op::Maximum => 1..=usize::MAX,
+            Op::GreaterThan | Op::LessThan | Op::EqualTo => 2..=2,
+        }
+    }
+
+    /// describe_arity describes the allowed number of `noun`s, such as "2 operands".
+    pub(super) fn describe_arity(self, noun: &str) -> String {
+        let arity = self.arity();
+        match *arity.end() {
+            usize::MAX => format!("at least {}", count(*arity.start(), noun)),
+            end => count(end, noun),
+        }
+    }
+
+    /// apply applies the operation to the values of the sub-packets.
+    pub fn apply<N: Number>(self, values: &[N]) -> Result<N, EvalError> {
+        if !self.arity().contains(&values.len()) {
+            return Err(EvalError::Arity {
+                op: self,
+                count: values.len(),
+            });
+        }
+        let overflow = || EvalError::Overflow {
+            op: self,
+            number: number_name::<N>(),
+        };
+        let boolean = |b| if b { N::one() } else { N::zero() };
+        let value = match self {
+            Op::Sum => values
+                .iter()
+                .try_fold(N::zero(), |acc, v| acc.checked_add(v))
+                .ok_or_else(overflow)?,
+            Op::Product => values
+                .iter()
+                .try_fold(N::one(), |acc, v| acc.checked_mul(v))
+                .ok_or_else(overflow)?,
+            Op::Minimum => values.iter().min().unwrap().clone(),
+            Op::Maximum => values.iter().max().unwrap().clone(),
+            Op::GreaterThan => boolean(values[0] > values[1]),
+            Op::LessThan => boolean(values[0] < values[1]),
+            Op::EqualTo => boolean(values[0] == values[1]),
+        };
+        Ok(value)
+    }
+}
+
+impl Packet {
+    /// decode decodes the outermost packet of a transmission.
+    pub fn decode(bytes: &[u8], padding: Padding) -> Result<Self, DecodeError> {
+        Packet::read_transmission(&mut BitReader::new(bytes), padding)
+    }
+
+    /// read_transmission reads the outermost packet and the padding after it, up to the
+    /// end of the stream.
+    pub fn read_transmission<R: Read>(
+        r: &mut BitReader<R>,
+        padding: Padding,
+    ) -> Result<Self, DecodeError> {
+        Packet::read_transmission_into(r, padding, None)
+    }
+
+    /// read_transmission_with_offsets is `read_transmission`, also returning the bit
+    /// offset of every packet in pre-order, the outermost packet first.
+    pub fn read_transmission_with_offsets<R: Read>(
+        r: &mut BitReader<R>,
+        padding: Padding,
+    ) -> Result<(Self, Vec<usize>), DecodeError> {
+        let mut offsets = vec![];
+        let packet = Packet::read_transmission_into(r, padding, Some(&mut offsets))?;
+        Ok((packet, offsets))
+    }
+
+    /// read_transmission_into is `read_transmission`, also adding the bit offset of every
+    /// packet to `offsets`, if any.
+    fn read_transmission_into<R: Read>(
+        r: &mut BitReader<R>,
+        padding: Padding,
+        offsets: Option<&mut Vec<usize>>,
+    ) -> Result<Self, DecodeError> {
+        let packet = Packet::read_packet(r, offsets, 1)?;
+        if padding == Padding::Lenient {
+            return Ok(packet);
+        }
+
+        // The rest of the current byte, then whole bytes up to the end of the stream.
+        let mut bits = r.padding().map_err(|e| DecodeError::io(r, e))?;
+        loop {
+            if bits != 0 {
+                // The first set bit is `64 - leading_zeros` bits before the offset.
+                let first_one = r.offset() - (64 - bits.leading_zeros() as usize);
+                return Err(DecodeError::new(first_one, "non-zero padding"));
+            }
+            bits = match r.read_bits(8) {
+                Ok(bits) => bits,
+                Err(e) if e.kind() == io::ErrorKind::UnexpectedEof => return Ok(packet),
+                Err(e) => return Err(DecodeError::io(r, e)),
+            };
+        }
+    }
+
+    /// read reads the next packet.
+    pub fn read<R: Read>(r: &mut BitReader<R>) -> Result<Self, DecodeError> {
+        Packet::read_packet(r, None, 1)
+    }
+
+    /// offsets returns the bit offset of every packet in pre-order, as laid out by
+    /// `encode`. For a decoded packet they are the offsets it was decoded from.
+    pub fn offsets(&self) -> Result<Vec<usize>> {
+        let bytes = self.encode()?;
+        let mut r = BitReader::new(&bytes[..]);
+        let (_packet, offsets) = Packet::read_transmission_with_offsets(&mut r, Padding::Lenient)?;
+        Ok(offsets)
+    }
+
+    /// read_packet reads a packet `depth` packets from the outermost one, which is 1, adding
+    /// the offsets of it and its sub-packets to `offsets`, if any.
+    fn read_packet<R: Read>(
+        r: &mut BitReader<R>,
+        mut offsets: Option<&mut Vec<usize>>,
+        depth: usize,
+    ) -> Result<Self, DecodeError> {
+        let start = r.offset();
+        if depth > MAX_DEPTH {
+            let reason = format!("packets nested deeper than {}", MAX_DEPTH);
+            return Err(DecodeError::new(start, reason));
+        }
+        if let Some(offsets) = offsets.as_deref_mut() {
+            offsets.push(start);
+        }
+        let version = read_bits(r, 3)? as Version;
+        let type_id = read_bits(r, 3)? as u8;
+        if type_id == LITERAL_TYPE_ID {
+            let (value, groups) = read_literal(r)?;
+            return Ok(Packet::Literal {
+                version,
+                value,
+                groups,
+            });
+        }
+
+        // Every other 3 bit type id is an operation.
+        let op = Op::from_type_id(type_id).unwrap();
+        let mut children = vec![];
+        let length_type = if read_bits(r, 1)? == 1 {
+            let count = read_bits(r, COUNT_BITS)?;
+            for _ in 0..count {
+                children.push(Packet::read_packet(r, offsets.as_deref_mut(), depth + 1)?);
+            }
+            LengthType::Count
+        } else {
+            let len = read_bits(r, TOTAL_BITS_BITS)? as usize;
+            let end = r.offset() + len;
+            while r.offset() < end {
+                children.push(Packet::read_packet(r, offsets.as_deref_mut(), depth + 1)?);
+            }
+            if r.offset() > end {
+                let reason = format!(
+                    "sub-packets overrun their length of {} bits by {} bits",
+                    len,
+                    r.offset() - end
+                );
+                return Err(DecodeError::new(end, reason));
+            }
+            LengthType::TotalBits
+        };
+        if !op.arity().contains(&children.len()) {
+            let reason = format!(
+                "{} packet has {}, expected {}",
+                op.name(),
+                count(children.len(), "sub-packet"),
+                op.describe_arity("sub-packet")
+            );
+            return Err(DecodeError::new(start, reason));
+        }
+        Ok(Packet::Operator {
+            version,
+            op,
+            length_type,
+            children,
+        })
+    }
+
+    pub fn version(&self) -> Version {
+        match self {
+            Packet::Literal { version, .. } | Packet::Operator { version, .. } => *version,
+        }
+    }
+
+    /// children returns the sub-packets of the packet, none for literals.
+    pub fn children(&self) -> &[Packet] {
+        match self {
+            Packet::Literal { .. } => &[],
+            Packet::Operator { children, .. } => children,
+        }
+    }
+
+    /// version_sum is the sum of the versions of the packet and all its sub-packets.
+    pub fn version_sum(&self) -> u64 {
+        let mut sum = 0;
+        let mut stack = vec![self];
+        while let Some(packet) = stack.pop() {
+            sum += packet.version() as u64;
+            stack.extend(packet.children());
+        }
+        sum
+    }
+
+    /// eval evaluates the expression the packet represents using the number type `N`.
+    pub fn eval<N: Number>(&self) -> Result<N, EvalError> {
+        self.eval_at(1)
+    }
+
+    /// eval_at evaluates a packet `depth` packets from the outermost one, which is 1.
+    fn eval_at<N: Number>(&self, depth: usize) -> Result<N, EvalError> {
+        if depth > MAX_DEPTH {
+            return Err(EvalError::TooDeep {
+                max_depth: MAX_DEPTH,
+            });
+        }
+        match self {
+            Packet::Literal { value, .. } => {
+                N::from_literal(value).ok_or_else(|| EvalError::LiteralOverflow {
+                    number: number_name::<N>(),
+                })
+            }
+            Packet::Operator { op, children, .. } => {
+                let values = children
+                    .iter()
+                    .map(|child| child.eval_at(depth + 1))
+                    .collect::<Result<Vec<N>, _>>()?;
+                op.apply(&values)
+            }
+        }
+    }
+
+    /// pretty renders the packet tree, one packet per line, indenting sub-packets.
+    pub fn pretty(&self) -> String {
+        let mut s = String::new();
+        let mut stack = vec![(self, 0)];
+        while let Some((packet, depth)) = stack.pop() {
+            packet.write_pretty(&mut s, depth).unwrap();
+            stack.extend(
+                packet
+                    .children()
+                    .iter()
+                    .rev()
+                    .map(|child| (child, depth + 1)),
+            );
+        }
+        s
+    }
+
+    /// write_pretty writes the line of the packet, without its sub-packets.
+    fn write_pretty(&self, w: &mut impl Write, depth: usize) -> std::fmt::Result {
+        let indent = depth * 2;
+        match self {
+            Packet::Literal { version, value, .. } => {
+                writeln!(
+                    w,
+                    "{:indent$}v{} literal {}",
+                    "",
+                    version,
+                    value,
+                    indent = indent
+                )
+            }
+            Packet::Operator {
+                version,
+                op,
+                length_type,
+                ..
+            } => {
+                let length_type = match length_type {
+                    LengthType::TotalBits => "bits",
+                    LengthType::Count => "count",
+                };
+                writeln!(
+                    w,
+                    "{:indent$}v{} {} [{}]",
+                    "",
+                    version,
+                    op.name(),
+                    length_type,
+                    indent = indent
+                )
+            }
+        }
+    }
+}
+
+impl LengthType {
+    /// choose picks the length type with the shorter header that can hold `children`.
+    pub fn choose(children: &[Packet]) -> Self {
+        if children.len() < 1 << COUNT_BITS {
+            LengthType::Count
+        } else {
+            LengthType::TotalBits
+        }
+    }
+}
+
+/// TOTAL_BITS_BITS is the width of the sub-packet length of length type 0.
+const TOTAL_BITS_BITS: usize = 15;
+/// COUNT_BITS is the width of the sub-packet count of length type 1.
+const COUNT_BITS: usize = 11;
+
+impl Packet {
+    /// literal creates a literal packet of a 64 bit value.
+    pub fn literal(version: Version, value: u64) -> Self {
+        Packet::big_literal(version, value.into())
+    }
+
+    /// big_literal creates a literal packet encoded in the fewest groups.
+    pub fn big_literal(version: Version, value: BigUint) -> Self {
+        Packet::Literal {
+            version,
+            groups: value.to_radix_be(16).len(),
+            value,
+        }
+    }
+
+    /// operator creates an operator packet, choosing its length type from its children.
+    pub fn operator(version: Version, op: Op, children: Vec<Packet>) -> Self {
+        Packet::Operator {
+            version,
+            op,
+            length_type: LengthType::choose(&children),
+            children,
+        }
+    }
+
+    /// encode encodes the packet as a transmission, padded with zeros to whole bytes.
+    pub fn encode(&self) -> Result<Vec<u8>> {
+        let mut w = BitWriter::default();
+        self.write(&mut w, 1)?;
+        Ok(w.into_bytes())
+    }
+
+    /// to_hex encodes the packet as a hexadecimal transmission.
+    pub fn to_hex(&self) -> Result<String> {
+        Ok(bytes_into_hex(&self.encode()?))
+    }
+
+    /// write writes a packet `depth` packets from the outermost one, which is 1.
+    fn write(&self, w: &mut BitWriter, depth: usize) -> Result<()> {
+        if depth > MAX_DEPTH {
+            return Err(anyhow::anyhow!("packets nested deeper than {}", MAX_DEPTH));
+        }
+        let version = self.version();
+        if version > 7 {
+            return Err(anyhow::anyhow!(
+                "version {} does not fit in 3 bits",
+                version
+            ));
+        }
+        w.write_bits(version as u64, 3);
+        match self {
+            Packet::Literal { value, groups, .. } => {
+                w.write_bits(LITERAL_TYPE_ID as u64, 3);
+                // Pad the fewest groups needed with leading zero groups, if it had any.
+                let digits = value.to_radix_be(16);
+                let mut padded = vec![0; groups.saturating_sub(digits.len())];
+                padded.extend(digits);
+                for (i, &group) in padded.iter().enumerate() {
+                    w.write_bits((i + 1 < padded.len()) as u64, 1);
+                    w.write_bits(group as u64, 4);
+                }
+            }
+            Packet::Operator {
+                op,
+                length_type,
+                children,
+                ..
+            } => {
+                w.write_bits(op.type_id() as u64, 3);
+                match length_type {
+                    LengthType::TotalBits => {
+                        let mut sub = BitWriter::default();
+                        children
+                            .iter()
+                            .try_for_each(|child| child.write(&mut sub, depth + 1))?;
+                        if sub.len() >= 1 << TOTAL_BITS_BITS {
+                            return Err(anyhow::anyhow!(
+                                "{} bits of sub-packets do not fit in {} bits",
+                                sub.len(),
+                                TOTAL_BITS_BITS
+                            ));
+                        }
+                        w.write_bits(0, 1);
+                        w.write_bits(sub.len() as u64, TOTAL_BITS_BITS);
+                        w.append(&sub);
+                    }
+                    LengthType::Count => {
+                        if children.len() >= 1 << COUNT_BITS {
+                            return Err(anyhow::anyhow!(
+                                "{} sub-packets do not fit in {} bits",
+                                children.len(),
+                                COUNT_BITS
+                            ));
+                        }
+                        w.write_bits(1, 1);
+                        w.write_bits(children.len() as u64, COUNT_BITS);
+                        children
+                            .iter()
+                            .try_for_each(|child| child.write(w, depth + 1))?;
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// count describes `n` of `noun`, which is pluralised unless `n` is 1.
+pub(super) fn count(n: usize, noun: &str) -> String {
+    match n {
+        1 => format!("1 {}", noun),
+        n => format!("{} {}s", n, noun),
+    }
+}
+
+/// bytes_into_hex encodes bytes as upper case hexadecimal.
+pub fn bytes_into_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{:02X}", byte)).collect()
+}
+
+/// read_literal reads the groups of a literal, returning its value and number of groups.
+fn read_literal<R: Read>(r: &mut BitReader<R>) -> Result<(BigUint, usize), DecodeError> {
+    let mut groups = vec![];
+    loop {
+        let more = read_bits(r, 1)? == 1;
+        groups.push(read_bits(r, 4)? as u8);
+        if !more {
+            let value = BigUint::from_radix_be(&groups, 16).unwrap();
+            return Ok((value, groups.len()));
+        }
+    }
+}
+
+/// read_bits reads `n` bits, failing with the offset the stream ended or failed at.
+fn read_bits<R: Read>(r: &mut BitReader<R>, n: usize) -> Result<u64, DecodeError> {
+    r.read_bits(n).map_err(|e| DecodeError::io(r, e))
+}
+
+/// Padding is how the bits after the outermost packet of a transmission are checked.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Padding {
+    /// Lenient ignores the padding.
+    Lenient,
+    /// Strict requires every bit after the packet to be zero.
+    Strict,
+}
+
+/// DecodeError is a malformed transmission, and the bit offset it was found at.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DecodeError {
+    pub offset: usize,
+    pub reason: String,
+}
+
+impl DecodeError {
+    pub fn new(offset: usize, reason: impl Into<String>) -> Self {
+        Self {
+            offset,
+            reason: reason.into(),
+        }
+    }
+
+    fn io<R: Read>(r: &BitReader<R>, err: io::Error) -> Self {
+        let reason = match err.kind() {
+            io::ErrorKind::UnexpectedEof => "unexpected end of transmission".to_owned(),
+            _ => err.to_string(),
+        };
+        Self::new(r.offset(), reason)
+    }
+}
+
+impl std::fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "bit {}: {}", self.offset, self.reason)
+    }
+}
+
+impl std::error::Error for DecodeError {}
+
+#[test]
+fn test_packet() {
+    let packet =
+        Packet::decode(&get_input_hex("38006F45291200").unwrap(), Padding::Lenient).unwrap();
+    assert_eq!(
+        packet,
+        Packet::Operator {
+            version: 1,
+            op: Op::LessThan,
+            length_type: LengthType::TotalBits,
+            children: vec![Packet::literal(6, 10), Packet::literal(2, 20)],
+        }
+    );
+    assert_eq!(packet.eval::<u64>(), Ok(1));
+    assert_eq!(packet.version_sum(), 9);
+
+    let packet = Packet::decode(
+        &get_input_hex("9C0141080250320F1802104A08").unwrap(),
+        Padding::Lenient,
+    )
+    .unwrap();
+    assert_eq!(packet.eval::<u64>(), Ok(1));
+    assert_eq!(
+        packet.pretty(),
+        "v4 equal to [bits]
+  v2 sum [count]
+    v2 literal 1
+    v4 literal 3
+  v6 product [count]
+    v0 literal 2
+    v2 literal 2
+"
+    );
+}
+
+#[test]
+fn test_encode() {
+    let transmissions = [
+        "D2FE28",
+        "38006F45291200",
+        "EE00D40C823060",
+        "8A004A801A8002F478",
+        "620080001611562C8802118E34",
+        "C0015000016115A2E0802F182340",
+        "A0016C880162017C3686B18A3D4780",
+        "C200B40A82",
+        "04005AC33890",
+        "880086C3E88112",
+        "CE00C43D881120",
+        "D8005AC2A8F0",
+        "F600BC2D8F",
+        "9C005AC2F8F0",
+        "9C0141080250320F1802104A08",
+    ];
+    for hex in transmissions {
+        let packet = Packet::decode(&get_input_hex(hex).unwrap(), Padding::Lenient).unwrap();
+        assert_eq!(packet.to_hex().unwrap(), hex);
+    }
+
+    let packet = Packet::operator(
+        3,
+        Op::Maximum,
+        vec![Packet::literal(1, 0), Packet::literal(7, u64::MAX)],
+    );
+    assert_eq!(
+        Packet::decode(&packet.encode().unwrap(), Padding::Strict).unwrap(),
+        packet
+    );
+
+    // A literal of 0x75 in 3 groups, the first one zero.
+    let packet = Packet::decode(&get_input_hex("D21728").unwrap(), Padding::Strict).unwrap();
+    assert_eq!(
+        packet,
+        Packet::Literal {
+            version: 6,
+            value: 0x75u8.into(),
+            groups: 3
+        }
+    );
+    assert_ne!(packet, Packet::literal(6, 0x75));
+    assert_eq!(packet.to_hex().unwrap(), "D21728");
+    let sum = Packet::operator(0, Op::Sum, vec![packet, Packet::literal(0, 1)]);
+    assert_eq!(sum.offsets().unwrap(), [0, 18, 39]);
+
+    let packet = Packet::literal(8, 1);
+    assert!(packet.encode().is_err());
+
+    let mut deep = Packet::literal(0, 1);
+    for _ in 0..MAX_DEPTH {
+        deep = Packet::operator(0, Op::Sum, vec![deep]);
+    }
+    assert!(deep.encode().is_err());
+    assert_eq!(
+        deep.eval::<u64>(),
+        Err(EvalError::TooDeep {
+            max_depth: MAX_DEPTH
+        })
+    );
+    assert_eq!(deep.children()[0].eval::<u64>(), Ok(1));
+}
+
+#[test]
+fn test_decode_errors() {
+    let decode = |hex: &str, padding| {
+        let mut r = BitReader::new(HexReader::new(hex.as_bytes()));
+        Packet::read_transmission(&mut r, padding).map_err(|e| e.to_string())
+    };
+    assert!(decode("D2FE28", Padding::Strict).is_ok());
+    assert!(decode("D2FE2800", Padding::Strict).is_ok());
+    assert!(decode("D2FE29", Padding::Lenient).is_ok());
+    assert_eq!(
+        decode("D2FE29", Padding::Strict).unwrap_err(),
+        "bit 23: non-zero padding"
+    );
+    assert_eq!(
+        decode("D2FE2810", Padding::Strict).unwrap_err(),
+        "bit 27: non-zero padding"
+    );
+    assert_eq!(
+        decode("D2FE", Padding::Lenient).unwrap_err(),
+        "bit 16: unexpected end of transmission"
+    );
+    assert_eq!(
+        decode("D2FE2", Padding::Lenient).unwrap_err(),
+        "bit 16: odd number of hex digits"
+    );
+    // A less than packet of length type 1 with three literal sub-packets.
+    let three = Packet::operator(0, Op::Sum, vec![Packet::literal(0, 1); 3]);
+    let mut bytes = three.encode().unwrap();
+    bytes[0] |= Op::LessThan.type_id() << 2;
+    assert_eq!(
+        Packet::decode(&bytes, Padding::Lenient)
+            .unwrap_err()
+            .to_string(),
+        "bit 0: less than packet has 3 sub-packets, expected 2 sub-packets"
+    );
+    // Sums of a single sub-packet nested one deeper than the limit, each 18 bits long.
+    let mut w = BitWriter::default();
+    for _ in 0..MAX_DEPTH {
+        w.write_bits(Op::Sum.type_id() as u64, 6);
+        w.write_bits(1, 1);
+        w.write_bits(1, COUNT_BITS);
+    }
+    w.write_bits(LITERAL_TYPE_ID as u64, 6);
+    w.write_bits(1, 5);
+    assert_eq!(
+        Packet::decode(&w.into_bytes(), Padding::Lenient).unwrap_err(),
+        DecodeError::new(18 * MAX_DEPTH, "packets nested deeper than 256")
+    );
+    // Sub-packets 11 bits long in a total length of 10 bits.
+    assert_eq!(
+        decode("0000284080", Padding::Lenient).unwrap_err(),
+        "bit 32: sub-packets overrun their length of 10 bits by 1 bits"
+    );
+}
@@ -0,0 +1,139 @@
+//! The number types packets can be evaluated with.
+
+use std::fmt::Display;
+
+use num_bigint::BigUint;
+
+use super::Op;
+
+/// Number is a number type the evaluator can compute with. Fixed width types report
+/// values that do not fit instead of wrapping.
+pub trait Number: Clone + Ord + Sized {
+    fn zero() -> Self;
+    fn one() -> Self;
+    /// from_literal converts the value of a literal packet, None if it does not fit.
+    fn from_literal(value: &BigUint) -> Option<Self>;
+    fn checked_add(&self, other: &Self) -> Option<Self>;
+    fn checked_mul(&self, other: &Self) -> Option<Self>;
+}
+
+macro_rules! impl_number {
+    ($($t:ty),*) => {
+        $(
+            impl Number for $t {
+                fn zero() -> Self {
+                    0
+                }
+
+                fn one() -> Self {
+                    1
+                }
+
+                fn from_literal(value: &BigUint) -> Option<Self> {
+                    <$t>::try_from(value).ok()
+                }
+
+                fn checked_add(&self, other: &Self) -> Option<Self> {
+                    <$t>::checked_add(*self, *other)
+                }
+
+                fn checked_mul(&self, other: &Self) -> Option<Self> {
+                    <$t>::checked_mul(*self, *other)
+                }
+            }
+        )*
+    };
+}
+
+impl_number!(u64, u128);
+
+impl Number for BigUint {
+    fn zero() -> Self {
+        BigUint::from(0u8)
+    }
+
+    fn one() -> Self {
+        BigUint::from(1u8)
+    }
+
+    fn from_literal(value: &BigUint) -> Option<Self> {
+        Some(value.clone())
+    }
+
+    fn checked_add(&self, other: &Self) -> Option<Self> {
+        Some(self + other)
+    }
+
+    fn checked_mul(&self, other: &Self) -> Option<Self> {
+        Some(self * other)
+    }
+}
+
+/// EvalError is a packet that can not be evaluated.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum EvalError {
+    /// LiteralOverflow is a literal too large for the number type.
+    LiteralOverflow { number: &'static str },
+    /// Overflow is an operation whose result is too large for the number type.
+    Overflow { op: Op, number: &'static str },
+    /// Arity is an operation with the wrong number of sub-packets.
+    Arity { op: Op, count: usize },
+    /// TooDeep is a packet nested deeper than `max_depth` packets.
+    TooDeep { max_depth: usize },
+}
+
+impl Display for EvalError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            EvalError::LiteralOverflow { number } => write!(f, "literal overflows {}", number),
+            EvalError::Overflow { op, number } => write!(f, "{} overflows {}", op.name(), number),
+            EvalError::Arity { op, count } => write!(
+                f,
+                "{} has {}, expected {}",
+                op.name(),
+                super::count(*count, "operand"),
+                op.describe_arity("operand")
+            ),
+            EvalError::TooDeep { max_depth } => {
+                write!(f, "packets nested deeper than {}", max_depth)
+            }
+        }
+    }
+}
+
+impl std::error::Error for EvalError {}
+
+/// number_name is the short name of a number type, such as `u64`.
+pub(super) fn number_name<N>() -> &'static str {
+    let name = std::any::type_name::<N>();
+    name.rsplit("::").next().unwrap_or(name)
+}
+
+#[test]
+fn test_eval_numbers() {
+    use super::{expr, Packet, Padding};
+
+    // A literal of 20 groups, 80 bits.
+    let big = expr::compile("1208925819614629174706175").unwrap();
+    let decoded = Packet::decode(&big.encode().unwrap(), Padding::Strict).unwrap();
+    assert_eq!(decoded, big);
+    assert_eq!(
+        big.eval::<u64>(),
+        Err(EvalError::LiteralOverflow { number: "u64" })
+    );
+    assert_eq!(big.eval::<u128>(), Ok((1 << 80) - 1));
+
+    let product = expr::compile("1099511627776 * 1099511627776").unwrap();
+    assert_eq!(
+        product.eval::<u64>().unwrap_err().to_string(),
+        "product overflows u64"
+    );
+    assert_eq!(product.eval::<u128>(), Ok(1 << 80));
+
+    let sum = expr::compile("340282366920938463463374607431768211455 + 1").unwrap();
+    assert!(sum.eval::<u128>().is_err());
+    assert_eq!(
+        sum.eval::<BigUint>().unwrap().to_string(),
+        "340282366920938463463374607431768211456"
+    );
+}
@@ -0,0 +1,226 @@
+//! Step by step evaluation of packets, and statistics over packet trees, for debugging
+//! transmissions.
+
+use std::collections::BTreeMap;
+use std::fmt::{Display, Write};
+
+use anyhow::Result;
+
+use super::number::{EvalError, Number};
+use super::{Op, Packet, Version, MAX_DEPTH};
+use crate::advent::json;
+
+/// Trace is an evaluated packet and the traces of its sub-packets.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Trace<N> {
+    /// offset is the bit offset of the packet in its transmission.
+    pub offset: usize,
+    pub version: Version,
+    /// op is the operation of the packet, None for literals.
+    pub op: Option<Op>,
+    pub value: N,
+    pub children: Vec<Trace<N>>,
+}
+
+impl<N: Number> Trace<N> {
+    /// new evaluates the packet. The offsets are those of the packet's own encoding, which
+    /// are the offsets it was decoded from.
+    pub fn new(packet: &Packet) -> Result<Self> {
+        let mut offsets = packet.offsets()?.into_iter();
+        Self::eval(packet, &mut offsets, 1)
+    }
+
+    /// eval evaluates a packet `depth` packets from the outermost one, which is 1, taking
+    /// the offsets of it and its sub-packets from `offsets`.
+    fn eval(
+        packet: &Packet,
+        offsets: &mut impl Iterator<Item = usize>,
+        depth: usize,
+    ) -> Result<Self> {
+        if depth > MAX_DEPTH {
+            return Err(EvalError::TooDeep {
+                max_depth: MAX_DEPTH,
+            }
+            .into());
+        }
+        let offset = offsets
+            .next()
+            .ok_or_else(|| anyhow::Error::msg("no offset for a packet"))?;
+        let (op, value, children) = match packet {
+            Packet::Literal { .. } => (None, packet.eval()?, vec![]),
+            Packet::Operator { op, children, .. } => {
+                let children = children
+                    .iter()
+                    .map(|child| Self::eval(child, offsets, depth + 1))
+                    .collect::<Result<Vec<_>>>()?;
+                let values = children.iter().map(|c| c.value.clone()).collect::<Vec<_>>();
+                (Some(*op), op.apply(&values)?, children)
+            }
+        };
+        Ok(Self {
+            offset,
+            version: packet.version(),
+            op,
+            value,
+            children,
+        })
+    }
+
+    /// operands are the values of the sub-packets.
+    pub fn operands(&self) -> impl Iterator<Item = &N> {
+        self.children.iter().map(|child| &child.value)
+    }
+}
+
+impl<N: Number + Display> Trace<N> {
+    /// type_name is the name of the operation, or `literal`.
+    fn type_name(&self) -> &'static str {
+        self.op.map_or("literal", Op::name)
+    }
+
+    /// render renders the trace as a tree, one packet per line, indenting sub-packets.
+    pub fn render(&self) -> String {
+        let mut s = String::new();
+        self.write_tree(&mut s, 0).unwrap();
+        s
+    }
+
+    fn write_tree(&self, w: &mut impl Write, depth: usize) -> std::fmt::Result {
+        write!(
+            w,
+            "{:indent$}bit {}: v{} {}",
+            "",
+            self.offset,
+            self.version,
+            self.type_name(),
+            indent = depth * 2
+        )?;
+        if self.op.is_some() {
+            let operands = self.operands().map(|v| v.to_string()).collect::<Vec<_>>();
+            write!(w, " [{}]", operands.join(", "))?;
+        }
+        writeln!(w, " = {}", self.value)?;
+        self.children
+            .iter()
+            .try_for_each(|child| child.write_tree(w, depth + 1))
+    }
+
+    /// to_json returns the trace as nested JSON objects with the fields `offset`,
+    /// `version`, `type`, `operands`, `value` and `children`.
+    pub fn to_json(&self) -> String {
+        let operands = self.operands().map(|v| v.to_string()).collect::<Vec<_>>();
+        let children = self.children.iter().map(Trace::to_json).collect::<Vec<_>>();
+        format!(
+            r#"{{"offset":{},"version":{},"type":{},"operands":[{}],"value":{},"children":[{}]}}"#,
+            self.offset,
+            self.version,
+            json::string(self.type_name()),
+            operands.join(","),
+            self.value,
+            children.join(",")
+        )
+    }
+}
+
+/// Stats are aggregate statistics of a packet tree.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Stats {
+    pub packets: usize,
+    pub literals: usize,
+    /// max_depth is the number of packets on the longest path from the outermost packet.
+    pub max_depth: usize,
+    /// versions is the number of packets with each version.
+    pub versions: BTreeMap<Version, usize>,
+    /// operators is the number of operator packets with each operation.
+    pub operators: BTreeMap<Op, usize>,
+}
+
+impl Stats {
+    pub fn new(packet: &Packet) -> Self {
+        let mut stats = Self::default();
+        let mut stack = vec![(packet, 1)];
+        while let Some((packet, depth)) = stack.pop() {
+            stats.add(packet, depth);
+            stack.extend(packet.children().iter().map(|child| (child, depth + 1)));
+        }
+        stats
+    }
+
+    /// add counts a packet `depth` packets from the outermost one, without its sub-packets.
+    fn add(&mut self, packet: &Packet, depth: usize) {
+        self.packets += 1;
+        self.max_depth = std::cmp::max(self.max_depth, depth);
+        *self.versions.entry(packet.version()).or_insert(0) += 1;
+        match packet {
+            Packet::Literal { .. } => self.literals += 1,
+            Packet::Operator { op, .. } => *self.operators.entry(*op).or_insert(0) += 1,
+        }
+    }
+}
+
+impl Display for Stats {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let versions = self.versions.iter().map(|(v, n)| format!("{}: {}", v, n));
+        let operators = self
+            .operators
+            .iter()
+            .map(|(op, n)| format!("{}: {}", op.name(), n));
+        writeln!(f, "packets: {}", self.packets)?;
+        writeln!(f, "literals: {}", self.literals)?;
+        writeln!(f, "max depth: {}", self.max_depth)?;
+        writeln!(f, "versions: {}", versions.collect::<Vec<_>>().join(", "))?;
+        writeln!(f, "operators: {}", operators.collect::<Vec<_>>().join(", "))
+    }
+}
+
+#[test]
+fn test_trace() {
+    use super::{bits::BitReader, get_input_hex, Padding};
+
+    let bytes = get_input_hex("9C0141080250320F1802104A08").unwrap();
+    let mut r = BitReader::new(&bytes[..]);
+    let (packet, offsets) =
+        Packet::read_transmission_with_offsets(&mut r, Padding::Strict).unwrap();
+    assert_eq!(packet.offsets().unwrap(), offsets);
+
+    let trace = Trace::<u64>::new(&packet).unwrap();
+    assert_eq!(
+        trace.render(),
+        "bit 0: v4 equal to [4, 4] = 1
+  bit 22: v2 sum [1, 3] = 4
+    bit 40: v2 literal = 1
+    bit 51: v4 literal = 3
+  bit 62: v6 product [2, 2] = 4
+    bit 80: v0 literal = 2
+    bit 91: v2 literal = 2
+"
+    );
+    assert_eq!(
+        trace.children[1].to_json(),
+        r#"{"offset":62,"version":6,"type":"product","operands":[2,2],"value":4,"children":[{"offset":80,"version":0,"type":"literal","operands":[],"value":2,"children":[]},{"offset":91,"version":2,"type":"literal","operands":[],"value":2,"children":[]}]}"#
+    );
+
+    assert_eq!(
+        Stats::new(&packet).to_string(),
+        "packets: 7
+literals: 4
+max depth: 3
+versions: 0: 1, 2: 3, 4: 2, 6: 1
+operators: sum: 1, product: 1, equal to: 1
+"
+    );
+
+    // Offsets follow the literal groups of the transmission, leading zero groups included.
+    let literal = Packet::decode(&get_input_hex("D21728").unwrap(), Padding::Strict).unwrap();
+    let bytes = Packet::operator(0, Op::Sum, vec![literal, Packet::literal(0, 1)])
+        .encode()
+        .unwrap();
+    let mut r = BitReader::new(&bytes[..]);
+    let (packet, offsets) =
+        Packet::read_transmission_with_offsets(&mut r, Padding::Strict).unwrap();
+    let trace = Trace::<u64>::new(&packet).unwrap();
+    let traced = std::iter::once(trace.offset).chain(trace.children.iter().map(|c| c.offset));
+    assert_eq!(traced.collect::<Vec<_>>(), offsets);
+    assert_eq!(offsets, [0, 18, 39]);
+    assert_eq!(trace.value, 0x76);
+}
@@ -1,19 +1,48 @@
 use anyhow::Result;
 
+use super::geometry::{BoundingBox, Point2};
 use super::input;
+use super::{Answer, Solution};
 
-pub fn print_result(input: impl std::io::Read) -> Result<()> {
-    let target = parse_target_area(input)?;
-    let max_x = std::cmp::max(target.0.x, target.1.x);
+/// Solver solves the day 17 puzzle.
+pub struct Solver;
+
+impl Solution for Solver {
+    const DAY: u8 = 17;
+    const TITLE: &'static str = "Trick Shot";
+
+    type Input = Target;
+
+    fn parse(&self, input: impl std::io::Read) -> Result<Self::Input> {
+        parse_target_area(input)
+    }
+
+    fn part1(&self, target: &Self::Input) -> Result<Answer> {
+        // highest y position reached by a trajectory that hits the target
+        let (peak_position, _) = search_trajectories(*target);
+        Ok(peak_position.y.into())
+    }
+
+    fn part2(&self, target: &Self::Input) -> Result<Answer> {
+        // number of distinct velocities to hit
+        let (_, hit_count) = search_trajectories(*target);
+        Ok(hit_count.into())
+    }
+}
+
+/// search_trajectories tries every initial velocity that could hit the target, returning
+/// the highest peak position reached and the number of velocities that hit the target.
+pub fn search_trajectories(target: Target) -> (Position, usize) {
+    let max_x = target.max.x;
     // only relevant if target is below origin. Since y velocity increases every tick
     // it will overshoot past this.
-    let min_y = std::cmp::min(target.0.y, target.1.y).abs();
+    let min_y = target.min.y.abs();
 
-    let mut peak_position: Position = (0, 0).into();
+    let mut peak_position = Position::default();
     let mut hit_count = 0;
     for yv in -min_y..=min_y {
         for xv in 0..=max_x {
-            let (hit, peak) = simulate_trajectory((xv, yv), target);
+            let (hit, peak) = simulate_trajectory(Velocity::new(xv, yv), target);
             if !hit {
                 continue;
             }
@@ -23,38 +52,22 @@ pub fn print_result(input: impl std::io::Read) -> Result<()> {
             }
         }
     }
-
-    println!(
-        "Part 1: Found peak at (x={}, y={})",
-        peak_position.x, peak_position.y
-    );
-
-    println!(
-        "Part 2: Number of distinct velocities to hit = {}",
-        hit_count,
-    );
-
-    Ok(())
+    (peak_position, hit_count)
 }
 
-// returns true if target is hit, and returns the *peak* position in the arc.
-fn simulate_trajectory(
-    initial_velocity: (isize, isize),
-    target: (Position, Position),
-) -> (bool, Position) {
+/// simulate_trajectory returns true if target is hit, and returns the *peak* position in the arc.
+pub(crate) fn simulate_trajectory(initial_velocity: Velocity, target: Target) -> (bool, Position) {
     let mut probe = Probe::new(initial_velocity);
-    let x_lim = std::cmp::max(target.0.x, target.1.x);
-    let y_lim = std::cmp::min(target.0.y, target.1.y);
 
     let mut peak = probe.pos;
     let mut hit = false;
 
-    while probe.pos.x <= x_lim && probe.pos.y >= y_lim {
+    while probe.pos.x <= target.max.x && probe.pos.y >= target.min.y {
         probe.tick();
         if peak.y <= probe.pos.y {
             peak = probe.pos
         }
-        if probe.within_bounds(target) {
+        if target.contains(probe.pos) {
             hit = true;
             break;
         }
@@ -63,84 +76,53 @@ fn simulate_trajectory(
 }
 
 struct Probe {
-    vx: isize,
-    vy: isize,
+    velocity: Velocity,
     pos: Position,
 }
 
 impl Probe {
-    fn new(initial_velocity: (isize, isize)) -> Self {
+    fn new(initial_velocity: Velocity) -> Self {
         Self {
-            vx: initial_velocity.0,
-            vy: initial_velocity.1,
-            pos: (0, 0).into(),
+            velocity: initial_velocity,
+            pos: Position::default(),
         }
     }
 
     fn tick(&mut self) {
-        self.pos.x += self.vx;
-        self.pos.y += self.vy;
-        if self.vx > 0 {
-            self.vx -= 1;
-        } else if self.vx < 0 {
-            self.vx += 1;
-        }
-        self.vy -= 1;
-    }
-
-    fn within_bounds(&self, bounds: (Position, Position)) -> bool {
-        let x_within = self.pos.x <= std::cmp::max(bounds.0.x, bounds.1.x)
-            && self.pos.x >= std::cmp::min(bounds.0.x, bounds.1.x);
-        let y_within = self.pos.y <= std::cmp::max(bounds.0.y, bounds.1.y)
-            && self.pos.y >= std::cmp::min(bounds.0.y, bounds.1.y);
-        x_within && y_within
+        self.pos += self.velocity;
+        // drag pulls x velocity towards 0, gravity pulls y velocity down.
+        self.velocity.x -= self.velocity.x.signum();
+        self.velocity.y -= 1;
     }
 }
 
-fn parse_target_area(input: impl std::io::Read) -> Result<(Position, Position)> {
-    let lines = input::get_input_lines(input)?;
-    let line = lines.get(0).ok_or(anyhow::Error::msg("bad input"))?;
-    if !line.starts_with("target area: ") {
-        return Err(anyhow::Error::msg("bad input"));
-    }
-    let mut positions = (Position::new(0, 0), Position::new(0, 0));
-    for part in line
+/// parse_target_area parses `target area: x=a..b, y=c..d` into the target area.
+pub fn parse_target_area(input: impl std::io::Read) -> Result<Target> {
+    let line = input::get_input_line(input)?;
+    let ranges = line
+        .field()
+        .trim()
         .strip_prefix("target area: ")
-        .ok_or(anyhow::Error::msg("bad input"))?
-        .split(", ")
-    {
-        let (xy, range) = part
-            .split_once("=")
-            .ok_or(anyhow::Error::msg("bad input"))?;
-        let (pos1, pos2) = match xy {
-            "x" => (&mut positions.0.x, &mut positions.1.x),
-            "y" => (&mut positions.0.y, &mut positions.1.y),
-            _ => return Err(anyhow::Error::msg("bad input")),
+        .ok_or_else(|| line.error(line.field(), "expected 'target area: '"))?;
+    let mut corners = (Position::default(), Position::default());
+    for (xy, range) in line.key_ranges(ranges)? {
+        let (pos1, pos2) = match xy.text {
+            "x" => (&mut corners.0.x, &mut corners.1.x),
+            "y" => (&mut corners.0.y, &mut corners.1.y),
+            _ => return Err(line.error(xy, "expected x or y").into()),
         };
-        let (start, end) = range
-            .split_once("..")
-            .ok_or(anyhow::Error::msg("bad input"))?;
-        *pos1 = start.parse()?;
-        *pos2 = end.parse()?;
+        *pos1 = *range.start();
+        *pos2 = *range.end();
     }
 
-    Ok(positions)
+    Ok(Target::new(corners.0, corners.1))
 }
 
-#[derive(Debug, Clone, Copy)]
-struct Position {
-    x: isize,
-    y: isize,
-}
+/// Position is a position of the probe, y increases upwards.
+pub type Position = Point2<isize>;
 
-impl Position {
-    fn new(x: isize, y: isize) -> Self {
-        Self { x, y }
-    }
-}
+/// Velocity is the distance the probe moves in one step.
+pub type Velocity = Point2<isize>;
 
-impl From<(isize, isize)> for Position {
-    fn from((x, y): (isize, isize)) -> Self {
-        Self { x, y }
-    }
-}
+/// Target is the target area, including its edges.
+pub type Target = BoundingBox<Position>;
@@ -1,43 +1,71 @@
 use std::vec;
 
-use super::input;
+use super::input::{self, NumberedLine, ParseError};
+use super::{Answer, Solution};
 use anyhow::Result;
 
-pub fn print_result(f: impl std::io::Read) -> Result<()> {
-    let lines = input::get_input_lines(f)?;
-    let numbers: Vec<Vec<Number>> = lines.iter().map(parse_snailfish_numbers).collect();
+/// Solver solves the day 18 puzzle.
+pub struct Solver;
 
-    println!(
-        "Snail number magnitude = {}",
-        calc_magnitude(&add_lines(&numbers))
-    );
+impl Solution for Solver {
+    const DAY: u8 = 18;
+    const TITLE: &'static str = "Snailfish";
+
+    type Input = Vec<Vec<Number>>;
+
+    fn parse(&self, f: impl std::io::Read) -> Result<Self::Input> {
+        let lines = input::get_numbered_lines(f)?;
+        let numbers = lines
+            .iter()
+            .filter(|l| !l.is_blank())
+            .map(parse_snailfish_numbers)
+            .collect::<Result<_, _>>()?;
+        Ok(numbers)
+    }
 
-    println!("Largest magnitude = {}", find_biggest_magnitude(&numbers));
+    fn part1(&self, numbers: &Self::Input) -> Result<Answer> {
+        // magnitude of the final sum
+        Ok(calc_magnitude(&add_lines(numbers)).into())
+    }
 
-    Ok(())
+    fn part2(&self, numbers: &Self::Input) -> Result<Answer> {
+        // largest magnitude of any sum of two numbers
+        Ok(find_biggest_magnitude(numbers).into())
+    }
 }
 
-fn parse_snailfish_numbers(input: &String) -> Vec<Number> {
+/// parse_snailfish_numbers parses the snailfish number on a line into its regular numbers
+/// and their depths.
+pub fn parse_snailfish_numbers(line: &NumberedLine) -> Result<Vec<Number>, ParseError> {
     let mut values = vec![];
-    let mut depth = 0;
-    for c in input.chars() {
+    let mut depth = 0usize;
+    let number = line.field().trim();
+    for (c, text) in number.chars() {
         match c {
             '[' => depth += 1,
-            ']' => depth -= 1,
+            ']' => {
+                depth = depth
+                    .checked_sub(1)
+                    .ok_or_else(|| line.error(text, "unmatched ']'"))?
+            }
             ',' => {}
             c => {
-                values.push(Number {
-                    value: c.to_digit(10).unwrap() as u32,
-                    depth: depth as usize,
-                });
+                let value = c
+                    .to_digit(10)
+                    .ok_or_else(|| line.error(text, "not a digit"))?;
+                values.push(Number { value, depth });
             }
         }
     }
+    if depth != 0 {
+        return Err(line.error(number, "unmatched '['"));
+    }
 
-    return values;
+    Ok(values)
 }
 
-fn find_biggest_magnitude(lines: &Vec<Vec<Number>>) -> u32 {
+/// find_biggest_magnitude returns the largest magnitude of the sum of any two numbers.
+pub fn find_biggest_magnitude(lines: &[Vec<Number>]) -> u32 {
     let mut max = 0;
     for i in 0..lines.len() {
         for j in 0..lines.len() {
@@ -48,27 +76,22 @@ fn find_biggest_magnitude(lines: &Vec<Vec<Number>>) -> u32 {
             }
         }
     }
-    return max;
+    max
 }
 
+/// Number is a regular number in a snailfish number, at a nesting depth.
 #[derive(Debug, Clone)]
-struct Number {
+pub struct Number {
     value: u32,
     depth: usize,
 }
 
-impl Number {
-    fn new(v: u32, d: usize) -> Self {
-        Number { value: v, depth: d }
-    }
-}
-
-/// perform snailfish addition on lines of numbers
-fn add_lines(lines: &[Vec<Number>]) -> Vec<Number> {
+/// add_lines performs snailfish addition on lines of numbers.
+pub fn add_lines(lines: &[Vec<Number>]) -> Vec<Number> {
     let mut num = lines[0].clone();
 
     for next in &lines[1..lines.len()] {
-        let mut sum = add(&num, &next);
+        let mut sum = add(&num, next);
         while reduce(&mut sum) {}
         num = sum;
     }
@@ -76,8 +99,9 @@ fn add_lines(lines: &[Vec<Number>]) -> Vec<Number> {
     num
 }
 
-fn calc_magnitude(input: &Vec<Number>) -> u32 {
-    let mut curr = input.clone();
+/// calc_magnitude returns the magnitude of a snailfish number.
+pub fn calc_magnitude(input: &[Number]) -> u32 {
+    let mut curr = input.to_vec();
 
     loop {
         if curr.len() == 1 {
@@ -159,7 +183,7 @@ fn split(input: &mut Vec<Number>, at_index: usize) -> bool {
     true
 }
 
-fn add(a: &Vec<Number>, b: &Vec<Number>) -> Vec<Number> {
+fn add(a: &[Number], b: &[Number]) -> Vec<Number> {
     a.iter()
         .chain(b.iter())
         .map(|v| Number {
@@ -2,83 +2,89 @@ use std::collections::HashSet;
 
 use anyhow::Result;
 
-pub fn print_result(mut input: impl std::io::Read) -> Result<()> {
-    let mut buf = String::new();
-    input.read_to_string(&mut buf)?;
-    let scanners: Vec<_> = buf.split("\n\n").map(Scanner::parse).collect();
+use super::geometry::Point3;
+use super::input::{self, NumberedLine, ParseError};
+use super::{Answer, Solution};
 
-    let count = get_total_beacons(scanners)?;
+/// Solver solves the day 19 puzzle.
+pub struct Solver;
 
-    println!("Number of beacons = {}", count);
+impl Solution for Solver {
+    const DAY: u8 = 19;
+    const TITLE: &'static str = "Beacon Scanner";
 
-    Ok(())
+    type Input = Vec<Scanner>;
+
+    fn parse(&self, mut input: impl std::io::Read) -> Result<Self::Input> {
+        let mut buf = String::new();
+        input.read_to_string(&mut buf)?;
+        parse(&buf)
+    }
+
+    fn part1(&self, scanners: &Self::Input) -> Result<Answer> {
+        Ok(align(scanners.clone())?.beacons.into())
+    }
+
+    fn part2(&self, scanners: &Self::Input) -> Result<Answer> {
+        Ok(align(scanners.clone())?.max_distance.into())
+    }
 }
 
-fn parse(input: &str) -> Result<Vec<Scanner>> {
-    let v: Vec<_> = input.split("\n\n").map(Scanner::parse).collect();
-    Ok(v)
+/// parse parses the beacon reports of every scanner.
+pub fn parse(input: &str) -> Result<Vec<Scanner>> {
+    let mut scanners: Vec<Scanner> = vec![];
+    for section in input::get_input_sections(input.as_bytes())? {
+        let mut scanner = Scanner::parse_header(&section[0])?;
+        scanner.beacons = section[1..]
+            .iter()
+            .map(parse_beacon)
+            .collect::<Result<_, _>>()?;
+        scanners.push(scanner);
+    }
+    Ok(scanners)
 }
 
-fn manhattan() {
-    let precomputed_pos = r"0,0,0
--63,-1315,-111
--143,-2477,-86
--26,-1217,1093
--94,-2376,-1345
-22,-3572,-1159
--106,-3706,-2449
--1221,-3606,-1168
--2464,-3573,-1227
--2540,-2433,-1155
--1224,-4801,-1291
--2544,-4815,-1258
--1333,-3618,-24
--2506,-3612,-2528
--2543,-4769,-88
--3607,-3589,-2467
--2521,-3593,-3700
--2350,-5964,-1282
--2418,-3746,37
--1219,-3654,1060
--4867,-3702,-2531
--3620,-6069,-1317
--1233,-3560,-3625
--3728,-7224,-1252
--2476,-3556,-4761
--2437,-7347,-1231
--3722,-6019,-87
--2434,-7343,40";
-    let positions = parse_beacons(precomputed_pos.lines());
-    let mut max_dist = 0;
-    for i in 0..positions.len() {
-        for j in 0..positions.len() {
-            let distance = (positions[i][0] - positions[j][0]).abs()
-                + (positions[i][1] - positions[j][1]).abs()
-                + (positions[i][2] - positions[j][2]).abs();
-            max_dist = if distance > max_dist {
-                distance
-            } else {
-                max_dist
-            }
-        }
+/// parse_beacon parses an `x,y,z` beacon position.
+fn parse_beacon(line: &NumberedLine) -> Result<Beacon, ParseError> {
+    match line.numbers(line.field(), ",")?[..] {
+        [x, y, z] => Ok(Beacon::new(x, y, z)),
+        _ => Err(line.error(line.field(), "expected 'x,y,z'")),
     }
-    println!("Maxiumum distace = {}", max_dist)
 }
 
-fn parse_beacons<'a>(s: impl Iterator<Item = &'a str>) -> Vec<Beacon> {
-    s.map(|line| {
-        let mut nums = line.split(',').map(|x| x.parse::<i32>().unwrap());
-        [
-            nums.next().unwrap(),
-            nums.next().unwrap(),
-            nums.next().unwrap(),
-        ]
-    })
-    .collect::<Vec<_>>()
+/// Alignment is every scanner aligned to the first one.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Alignment {
+    /// beacons is the number of distinct beacons.
+    pub beacons: usize,
+    /// scanners are the scanners in input order, relative to the first one.
+    pub scanners: Vec<ScannerPosition>,
+    /// max_distance is the largest Manhattan distance between any two scanners.
+    pub max_distance: i32,
+}
+
+/// ScannerPosition is the position and orientation of a scanner, relative to the first one.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ScannerPosition {
+    pub id: i32,
+    pub pos: Beacon,
+    /// rotation turns positions relative to the scanner to the orientation of the first
+    /// one.
+    pub rotation: Rotation,
 }
 
-fn get_total_beacons(mut scanners: Vec<Scanner>) -> Result<i32> {
-    scanners[0].pos = Some([0, 0, 0]);
+/// get_total_beacons aligns the scanners and returns the number of distinct beacons.
+pub fn get_total_beacons(scanners: Vec<Scanner>) -> Result<i32> {
+    Ok(align(scanners)?.beacons as i32)
+}
+
+/// align finds the position and orientation of every scanner relative to the first one.
+pub fn align(mut scanners: Vec<Scanner>) -> Result<Alignment> {
+    if scanners.is_empty() {
+        return Err(anyhow::Error::msg("no scanners"));
+    }
+    scanners[0].pos = Some(Beacon::default());
+    scanners[0].rotation = Rotation::new(0);
     let mut reference_scanners = vec![scanners[0].clone()];
     let mut beacons: HashSet<Beacon> = HashSet::new();
     for &beacon in reference_scanners[0].beacons.iter() {
@@ -86,8 +92,8 @@ fn get_total_beacons(mut scanners: Vec<Scanner>) -> Result<i32> {
     }
 
     while scanners.iter().filter(|s| s.pos.is_none()).count() > 0 {
+        let aligned = reference_scanners.len();
         for scanner in scanners.iter_mut().filter(|s| s.pos.is_none()) {
-            println!("Attempting to match scanner {}", scanner.id);
             let matched_scanner =
                 while_none(reference_scanners.iter(), scanner, |scanner, reference| {
                     if scanner.find_and_update_position(reference, 12) {
@@ -101,20 +107,38 @@ fn get_total_beacons(mut scanners: Vec<Scanner>) -> Result<i32> {
                     for &beacon in scanner.beacons.iter() {
                         beacons.insert(beacon);
                     }
-                    println!("Matched scanner {}", scanner.id);
                     reference_scanners.push(scanner)
                 }
                 None => continue,
             }
         }
+        if reference_scanners.len() == aligned {
+            let unaligned = scanners.iter().find(|s| s.pos.is_none()).unwrap();
+            return Err(anyhow::anyhow!(
+                "scanner {} overlaps no aligned scanner",
+                unaligned.id
+            ));
+        }
     }
 
-    for scanner in reference_scanners.iter() {
-        let p = scanner.pos.unwrap();
-        println!("{},{},{}", p[0], p[1], p[2]);
-    }
-
-    Ok(beacons.len() as i32)
+    let scanners = scanners
+        .into_iter()
+        .map(|s| ScannerPosition {
+            id: s.id,
+            pos: s.pos.unwrap(),
+            rotation: s.rotation.unwrap(),
+        })
+        .collect::<Vec<_>>();
+    let max_distance = scanners
+        .iter()
+        .flat_map(|a| scanners.iter().map(move |b| a.pos.manhattan(b.pos)))
+        .max()
+        .unwrap_or(0);
+    Ok(Alignment {
+        beacons: beacons.len(),
+        scanners,
+        max_distance,
+    })
 }
 
 fn while_none<T, U, R>(
@@ -130,21 +154,24 @@ fn while_none<T, U, R>(
     None
 }
 
+/// Scanner is a scanner and the beacons it detects, relative to itself.
 #[derive(Debug, Clone)]
-struct Scanner {
+pub struct Scanner {
     id: i32,
     // relative to reference
-    pos: Option<[i32; 3]>,
+    pos: Option<Beacon>,
     // rotation
     rotation: Option<Rotation>,
     // relative to scanner
     beacons: Vec<Beacon>,
 }
 
-type Beacon = [i32; 3];
+/// Beacon is a beacon position, relative to a scanner.
+pub type Beacon = Point3<i32>;
 
 impl Scanner {
-    fn new(b: Vec<[i32; 3]>) -> Self {
+    #[cfg(test)]
+    fn new(b: Vec<Beacon>) -> Self {
         Self {
             id: 0,
             pos: None,
@@ -153,17 +180,20 @@ impl Scanner {
         }
     }
 
-    fn parse(s: &str) -> Self {
-        let mut lines = s.lines();
-        let header = lines.next().unwrap();
-        let id = header.split(' ').nth(2).unwrap();
-        let beacons: Vec<Beacon> = parse_beacons(lines);
-        Self {
-            id: id.parse().unwrap(),
-            beacons: beacons,
+    /// parse_header parses a `--- scanner N ---` header into a scanner without beacons.
+    fn parse_header(line: &NumberedLine) -> Result<Self, ParseError> {
+        let id = line
+            .field()
+            .trim()
+            .strip_prefix("--- scanner ")
+            .and_then(|s| s.strip_suffix(" ---"))
+            .ok_or_else(|| line.error(line.field(), "expected '--- scanner N ---'"))?;
+        Ok(Self {
+            id: line.parse(id)?,
+            beacons: vec![],
             pos: None,
             rotation: None,
-        }
+        })
     }
 
     fn find_and_update_position(&mut self, reference: &Scanner, require_matches: usize) -> bool {
@@ -177,12 +207,12 @@ impl Scanner {
 
     /// set scanner's position, rotation and update beacons position to be in line with the
     /// provided reference value.
-    fn rebase(&mut self, position: [i32; 3], rotation: Rotation) {
+    fn rebase(&mut self, position: Beacon, rotation: Rotation) {
         self.pos = Some(position);
         self.rotation = Some(rotation.clone());
         self.beacons.iter_mut().for_each(|p| {
             *p = rotation.rotate_vector_position(*p);
-            *p = Self::add(*p, position);
+            *p += position;
         });
     }
 
@@ -190,7 +220,7 @@ impl Scanner {
         &self,
         reference: &Scanner,
         minimum_matches: usize,
-    ) -> Option<([i32; 3], Rotation)> {
+    ) -> Option<(Beacon, Rotation)> {
         for (rotation, vectors) in self.rotated_beacons() {
             // We need to compare offsets between positions, since no known reference position exists.
             // Both scanners do not necessarily have the same set of beacons, so we have to try multiple
@@ -218,7 +248,7 @@ impl Scanner {
                     */
                     if overlap_count >= minimum_matches {
                         let ref_pos = reference_offset;
-                        let scan_pos = Self::sub(offset, ref_pos);
+                        let scan_pos = ref_pos - offset;
 
                         return Some((scan_pos, rotation));
                     }
@@ -231,7 +261,7 @@ impl Scanner {
     /// rotate the observed beacons in the different possible orientations.
     fn rotated_beacons<'a>(
         &'a self,
-    ) -> impl Iterator<Item = (Rotation, impl Iterator<Item = [i32; 3]> + Clone + 'a)> + Clone + 'a
+    ) -> impl Iterator<Item = (Rotation, impl Iterator<Item = Beacon> + Clone + 'a)> + Clone + 'a
     {
         let mut r = 0usize;
         std::iter::from_fn(move || {
@@ -254,27 +284,14 @@ impl Scanner {
     fn position_offsets(
         pos: impl Iterator<Item = Beacon> + Clone,
     ) -> impl Iterator<Item = (Beacon, HashSet<Beacon>)> + Clone {
-        pos.clone().map(move |r| {
-            (
-                r,
-                pos.clone()
-                    .map(move |p| Scanner::sub(r, p))
-                    .collect::<HashSet<_>>(),
-            )
-        })
-    }
-
-    fn sub(p1: Beacon, p2: Beacon) -> Beacon {
-        [p2[0] - p1[0], p2[1] - p1[1], p2[2] - p1[2]]
-    }
-
-    fn add(p1: Beacon, p2: Beacon) -> Beacon {
-        [p2[0] + p1[0], p2[1] + p1[1], p2[2] + p1[2]]
+        pos.clone()
+            .map(move |r| (r, pos.clone().map(move |p| p - r).collect::<HashSet<_>>()))
     }
 }
 
-#[derive(Debug, Clone)]
-struct Rotation((i32, i32, i32, usize, usize, usize));
+/// Rotation is one of the 24 orientations a scanner can face.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Rotation((i32, i32, i32, usize, usize, usize));
 
 impl Rotation {
     fn new(i: usize) -> Option<Self> {
@@ -282,18 +299,32 @@ impl Rotation {
         Some(Self(o))
     }
 
-    fn rotate_vector_position(&self, pos: [i32; 3]) -> [i32; 3] {
-        [
+    /// matrix returns the rotation as a matrix, multiplying column vectors.
+    pub fn matrix(&self) -> [[i32; 3]; 3] {
+        let (signs, axes) = (
+            [self.0 .0, self.0 .1, self.0 .2],
+            [self.0 .3, self.0 .4, self.0 .5],
+        );
+        let mut matrix = [[0; 3]; 3];
+        for row in 0..3 {
+            matrix[row][axes[row]] = signs[row];
+        }
+        matrix
+    }
+
+    fn rotate_vector_position(&self, pos: Beacon) -> Beacon {
+        let pos: [i32; 3] = pos.into();
+        Beacon::new(
             pos[self.0 .3] * self.0 .0,
             pos[self.0 .4] * self.0 .1,
             pos[self.0 .5] * self.0 .2,
-        ]
+        )
     }
 
     fn get_vector_rotation_factors(i: usize) -> Option<(i32, i32, i32, usize, usize, usize)> {
         match i {
             i @ 0..=23 => Some(Self::VECTOR_ROTATION[i]),
-            _ => return None,
+            _ => None,
         }
     }
 
@@ -325,19 +356,60 @@ impl Rotation {
     ];
 }
 
+#[cfg(test)]
 mod test {
     use super::*;
 
     #[test]
-    fn test_manhattan() {
-        manhattan()
+    fn test_align() {
+        let scanners = parse(INPUT).expect("parse ok");
+        let first = scanners[1].beacons[0];
+        let alignment = align(scanners).expect("alignment");
+        assert_eq!(alignment.beacons, 79);
+        assert_eq!(alignment.max_distance, 3621);
+        let positions = alignment
+            .scanners
+            .iter()
+            .map(|s| (s.id, s.pos))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            positions,
+            [
+                (0, Beacon::new(0, 0, 0)),
+                (1, Beacon::new(68, -1246, -43)),
+                (2, Beacon::new(1105, -1205, 1229)),
+                (3, Beacon::new(-92, -2380, -20)),
+                (4, Beacon::new(-20, -1133, 1061)),
+            ]
+        );
+        assert_eq!(
+            alignment.scanners[0].rotation.matrix(),
+            [[1, 0, 0], [0, 1, 0], [0, 0, 1]]
+        );
+
+        // Scanner 1 sees the beacon at -618,-824,-621 as 686,422,578.
+        let scanner = &alignment.scanners[1];
+        let rotated = scanner
+            .rotation
+            .matrix()
+            .map(|row| row[0] * first.x + row[1] * first.y + row[2] * first.z);
+        assert_eq!(
+            Beacon::from(rotated) + scanner.pos,
+            Beacon::new(-618, -824, -621)
+        );
+
+        let scanners = parse("--- scanner 0 ---\n1,2,3\n\n--- scanner 1 ---\n4,5,6\n").unwrap();
+        assert_eq!(
+            align(scanners).unwrap_err().to_string(),
+            "scanner 1 overlaps no aligned scanner"
+        );
     }
 
     #[test]
     fn test_find_positions_input() {
         let mut scanners = parse(INPUT).expect("parse ok");
         // Set scanner 0 as reference position
-        scanners[0].pos = Some([0, 0, 0]);
+        scanners[0].pos = Some(Beacon::default());
 
         let s0 = scanners[0].clone();
         let mut s1 = scanners[1].clone();
@@ -346,8 +418,14 @@ mod test {
         let mut s4 = scanners[4].clone();
         s4.find_and_update_position(&s1, 12);
 
-        assert_eq!([68, -1246, -43], s1.pos.expect("s1 has gotten position"));
-        assert_eq!([-20, -1133, 1061], s4.pos.expect("s4 has gotten position"));
+        assert_eq!(
+            Beacon::new(68, -1246, -43),
+            s1.pos.expect("s1 has gotten position")
+        );
+        assert_eq!(
+            Beacon::new(-20, -1133, 1061),
+            s4.pos.expect("s4 has gotten position")
+        );
     }
 
     #[test]
@@ -360,14 +438,15 @@ mod test {
         -2 S
            0 1 2 3 4
          */
-        let mut s1 = Scanner::new(vec![[3, 3, 3], [2, 1, 0], [1, 2, 0]]);
-        s1.pos = Some([0, 0, 0]);
-        let mut s2 = Scanner::new(vec![[1, 4, 0], [7, 6, 5], [2, 3, 0]]);
+        let beacons = |b: &[[i32; 3]]| b.iter().map(|&b| Beacon::from(b)).collect::<Vec<_>>();
+        let mut s1 = Scanner::new(beacons(&[[3, 3, 3], [2, 1, 0], [1, 2, 0]]));
+        s1.pos = Some(Beacon::default());
+        let mut s2 = Scanner::new(beacons(&[[1, 4, 0], [7, 6, 5], [2, 3, 0]]));
         let matches = s2.find_and_update_position(&s1, 2);
 
-        assert_eq!(s2.pos.expect("position set"), [0, -2, 0]);
-        assert_eq!(matches, true);
-        assert_eq!(s2.beacons.as_slice(), &[[1, 2, 0], [7, 4, 5], [2, 1, 0]])
+        assert_eq!(s2.pos.expect("position set"), Beacon::new(0, -2, 0));
+        assert!(matches);
+        assert_eq!(s2.beacons, beacons(&[[1, 2, 0], [7, 4, 5], [2, 1, 0]]))
     }
 
     #[test]
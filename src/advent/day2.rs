@@ -2,23 +2,39 @@ use std::str::FromStr;
 
 use anyhow::Result;
 
-use super::input;
+use super::input::{self, NumberedLine, ParseError};
+use super::{Answer, Solution};
 
-pub fn print_position(input: impl std::io::Read) -> Result<()> {
-    let cmds = parse_commands(input)?;
+/// Solver solves the day 2 puzzle.
+pub struct Solver;
 
-    let mut pos = Position { x: 0, z: 0, v: 0 };
-    pos.part1_apply_commands(cmds.as_slice());
-    println!("Predicted Position: X*Z = {}", pos.value());
+impl Solution for Solver {
+    const DAY: u8 = 2;
+    const TITLE: &'static str = "Dive!";
 
-    let mut pos = Position { x: 0, z: 0, v: 0 };
-    pos.part2_apply_commands(cmds.as_slice());
-    println!("Predicted Position using Aim: X*Z = {}", pos.value());
+    type Input = Vec<Command>;
 
-    Ok(())
+    fn parse(&self, input: impl std::io::Read) -> Result<Self::Input> {
+        parse_commands(input)
+    }
+
+    fn part1(&self, cmds: &Self::Input) -> Result<Answer> {
+        let mut pos = Position::default();
+        pos.part1_apply_commands(cmds.as_slice());
+        Ok(pos.value().into())
+    }
+
+    fn part2(&self, cmds: &Self::Input) -> Result<Answer> {
+        // Predicted position using aim
+        let mut pos = Position::default();
+        pos.part2_apply_commands(cmds.as_slice());
+        Ok(pos.value().into())
+    }
 }
 
-struct Position {
+/// Position is the position of the submarine after applying commands.
+#[derive(Default)]
+pub struct Position {
     /// x track horizontal position
     x: i64,
     /// z tracks vertical position
@@ -28,7 +44,8 @@ struct Position {
 }
 
 impl Position {
-    fn part1_apply_commands(&mut self, cmds: &[Command]) {
+    /// part1_apply_commands moves the submarine, with up and down changing the depth.
+    pub fn part1_apply_commands(&mut self, cmds: &[Command]) {
         for cmd in cmds {
             match cmd {
                 Command::Forward(n) => self.x += n,
@@ -39,7 +56,8 @@ impl Position {
         }
     }
 
-    fn part2_apply_commands(&mut self, cmds: &[Command]) {
+    /// part2_apply_commands moves the submarine, with up and down changing the aim.
+    pub fn part2_apply_commands(&mut self, cmds: &[Command]) {
         for cmd in cmds {
             match cmd {
                 Command::Forward(n) => {
@@ -53,20 +71,42 @@ impl Position {
         }
     }
 
-    fn value(&self) -> i64 {
+    /// value is the horizontal position multiplied by the depth.
+    pub fn value(&self) -> i64 {
         self.x * self.z
     }
 }
 
-enum Command {
+/// Command is a single submarine command, such as `forward 5`.
+pub enum Command {
     Forward(i64),
     Backward(i64),
     Up(i64),
     Down(i64),
 }
 
+impl Command {
+    /// parse parses a command from a line of input.
+    pub fn parse(line: &NumberedLine) -> Result<Self, ParseError> {
+        let (cmd, n) = line
+            .field()
+            .trim()
+            .split_once(" ")
+            .ok_or_else(|| line.error(line.field(), "expected a command"))?;
+        let n = line.parse(n)?;
+        let cmd = match cmd.text {
+            "forward" => Command::Forward(n),
+            "backward" => Command::Backward(n),
+            "up" => Command::Up(n),
+            "down" => Command::Down(n),
+            _ => return Err(line.error(cmd, "unknown command")),
+        };
+        Ok(cmd)
+    }
+}
+
 impl TryFrom<&str> for Command {
-    type Error = anyhow::Error;
+    type Error = ParseError;
 
     fn try_from(value: &str) -> Result<Self, Self::Error> {
         Command::from_str(value)
@@ -74,25 +114,22 @@ impl TryFrom<&str> for Command {
 }
 
 impl FromStr for Command {
-    type Err = anyhow::Error;
+    type Err = ParseError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        // Can we do this with a match and destructuring?
-        let cmd = match s.split_once(" ") {
-            Some(("forward", n)) => Command::Forward(n.parse()?),
-            Some(("backward", n)) => Command::Backward(n.parse()?),
-            Some(("up", n)) => Command::Up(n.parse()?),
-            Some(("down", n)) => Command::Down(n.parse()?),
-            _ => return Err(anyhow::format_err!("bad command")),
-        };
-        Ok(cmd)
+        Command::parse(&NumberedLine {
+            number: 1,
+            text: s.to_owned(),
+        })
     }
 }
 
-fn parse_commands(input: impl std::io::Read) -> Result<Vec<Command>> {
-    input::get_input_lines(input)?
+/// parse_commands parses one command per line.
+pub fn parse_commands(input: impl std::io::Read) -> Result<Vec<Command>> {
+    let commands = input::get_numbered_lines(input)?
         .iter()
-        .map(String::as_str)
-        .map(Command::from_str)
-        .collect()
+        .filter(|line| !line.is_blank())
+        .map(Command::parse)
+        .collect::<Result<_, _>>()?;
+    Ok(commands)
 }
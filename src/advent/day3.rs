@@ -1,35 +1,42 @@
-use super::input;
+use super::{input, Answer, Solution};
 use anyhow::Result;
 
-pub fn print_power_consumption_and_life_support_rating(input: impl std::io::Read) -> Result<()> {
-    let lines = input::get_input_lines(input)?;
+/// Solver solves the day 3 puzzle.
+pub struct Solver;
 
-    let (g, e) = get_gamma_epsilon(lines.clone())?;
-    println!(
-        "Gamma = {}, Epsilon = {}, PowerConsumption = {}",
-        g,
-        e,
-        g * e
-    );
+impl Solution for Solver {
+    const DAY: u8 = 3;
+    const TITLE: &'static str = "Binary Diagnostic";
 
-    let rows: Vec<Vec<char>> = lines
-        .clone()
-        .into_iter()
-        .map(|s| s.chars().collect::<Vec<char>>())
-        .collect();
+    type Input = Vec<String>;
 
-    let oxygen = get_row_by_column_operation(rows.clone(), most_common)?;
+    fn parse(&self, input: impl std::io::Read) -> Result<Self::Input> {
+        let mut lines = input::get_input_lines(input)?;
+        for (i, line) in lines.iter().enumerate() {
+            input::check_chars(
+                i + 1,
+                line,
+                |c| c == '0' || c == '1',
+                "expected a binary digit",
+            )?;
+        }
+        lines.retain(|l| !l.is_empty());
+        Ok(lines)
+    }
 
-    let co2 = get_row_by_column_operation(rows.clone(), least_common)?;
-    println!(
-        "Life support rating = {}",
-        i64::from_str_radix(oxygen.into_iter().collect::<String>().as_str(), 2)?
-            * i64::from_str_radix(co2.into_iter().collect::<String>().as_str(), 2)?
-    );
+    fn part1(&self, lines: &Self::Input) -> Result<Answer> {
+        // power consumption
+        let (g, e) = get_gamma_epsilon(lines.clone())?;
+        Ok((g * e).into())
+    }
 
-    Ok(())
+    fn part2(&self, lines: &Self::Input) -> Result<Answer> {
+        Ok(get_life_support_rating(lines)?.into())
+    }
 }
 
+/// get_gamma_epsilon returns the gamma rate (most common bits) and epsilon rate (least
+/// common bits) of the diagnostic report.
 pub fn get_gamma_epsilon(lines: Vec<String>) -> Result<(i64, i64)> {
     let columns = input::lines_as_char_columns(lines);
     let (gamma_str, epsilon_str) =
@@ -48,6 +55,23 @@ pub fn get_gamma_epsilon(lines: Vec<String>) -> Result<(i64, i64)> {
     ))
 }
 
+/// get_life_support_rating returns the oxygen generator rating multiplied by the CO2
+/// scrubber rating.
+pub fn get_life_support_rating(lines: &[String]) -> Result<i64> {
+    let rows: Vec<Vec<char>> = lines
+        .iter()
+        .map(|s| s.chars().collect::<Vec<char>>())
+        .collect();
+
+    let oxygen = get_row_by_column_operation(rows.clone(), most_common)?;
+    let co2 = get_row_by_column_operation(rows, least_common)?;
+
+    Ok(
+        i64::from_str_radix(oxygen.into_iter().collect::<String>().as_str(), 2)?
+            * i64::from_str_radix(co2.into_iter().collect::<String>().as_str(), 2)?,
+    )
+}
+
 fn most_least_common_bits(chars: Vec<char>) -> (char, char) {
     let n_ones = chars.iter().filter(|c| **c == '1').count();
     let n_zeroes = chars.iter().filter(|c| **c == '0').count();
@@ -95,15 +119,10 @@ type Column = Vec<char>;
 
 fn get_row_by_column_operation(input: Vec<Row>, op: impl Fn(Column) -> char) -> Result<Row> {
     let n_columns = input[0].len();
-    let mut input: Vec<Option<Row>> = input.into_iter().map(|x| Some(x)).collect();
+    let mut input: Vec<Option<Row>> = input.into_iter().map(Some).collect();
 
     for col in 0..n_columns {
-        let column_data: Column = input
-            .clone()
-            .into_iter()
-            .filter_map(|x| x)
-            .map(|x| x[col])
-            .collect();
+        let column_data: Column = input.iter().flatten().map(|x| x[col]).collect();
         // Find the bit to keep.
         let bit_to_keep = op(column_data);
 
@@ -125,7 +144,7 @@ fn get_row_by_column_operation(input: Vec<Row>, op: impl Fn(Column) -> char) ->
         }
     }
 
-    let output: Vec<Row> = input.into_iter().filter_map(|x| x).collect();
+    let output: Vec<Row> = input.into_iter().flatten().collect();
     if output.len() != 1 {
         panic!("too many left = {}", output.len())
     }
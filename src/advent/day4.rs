@@ -1,9 +1,40 @@
 use anyhow::Error;
 use anyhow::Result;
 
-use super::input;
+use super::input::{self, NumberedLine, ParseError};
+use super::{Answer, Solution};
 
-struct BingoBoard {
+/// Solver solves the day 4 puzzle.
+pub struct Solver;
+
+impl Solution for Solver {
+    const DAY: u8 = 4;
+    const TITLE: &'static str = "Giant Squid";
+
+    type Input = (Vec<BingoNumber>, Vec<BingoBoard>);
+
+    fn parse(&self, input: impl std::io::Read) -> Result<Self::Input> {
+        parse_bingo_input(input)
+    }
+
+    fn part1(&self, (draw, boards): &Self::Input) -> Result<Answer> {
+        let (winning, _) = get_winning_and_losing_board(boards.clone(), draw.as_slice());
+        let winning = winning.ok_or_else(|| Error::msg("no bingo board won"))?;
+        let (score, _draw) = winning.score(draw.as_slice()).unwrap();
+        Ok(score.into())
+    }
+
+    fn part2(&self, (draw, boards): &Self::Input) -> Result<Answer> {
+        let (_, losing) = get_winning_and_losing_board(boards.clone(), draw.as_slice());
+        let losing = losing.ok_or_else(|| Error::msg("no bingo board lost"))?;
+        let (score, _draw) = losing.score(draw.as_slice()).unwrap();
+        Ok(score.into())
+    }
+}
+
+/// BingoBoard is a 5x5 bingo board.
+#[derive(Clone)]
+pub struct BingoBoard {
     numbers: Vec<BingoNumber>,
 }
 
@@ -23,11 +54,6 @@ impl BingoBoard {
         self.numbers[n * Self::SIZE..(n + 1) * Self::SIZE].iter()
     }
 
-    fn row_mut(&mut self, n: usize) -> impl Iterator<Item = &mut BingoNumber> {
-        let n = n % (self.numbers.len() / Self::SIZE);
-        self.numbers[n * Self::SIZE..(n + 1) * Self::SIZE].iter_mut()
-    }
-
     fn column(&self, n: usize) -> impl Iterator<Item = &BingoNumber> {
         let n = n % (self.numbers.len() / Self::SIZE);
         self.numbers.iter().enumerate().filter_map(move |(i, x)| {
@@ -39,22 +65,6 @@ impl BingoBoard {
         })
     }
 
-    fn column_mut(&mut self, n: usize) -> impl Iterator<Item = &mut BingoNumber> {
-        let n = n % (self.numbers.len() / Self::SIZE);
-        self.numbers
-            .iter_mut()
-            .enumerate()
-            .filter_map(
-                move |(i, x)| {
-                    if (i % Self::SIZE) == n {
-                        Some(x)
-                    } else {
-                        None
-                    }
-                },
-            )
-    }
-
     /// score will return the score and the number of draws to win or None.
     pub fn score(&self, draw: &[BingoNumber]) -> Option<(Score, usize)> {
         if draw.len() < 5 {
@@ -87,31 +97,17 @@ impl BingoBoard {
     }
 
     fn calc_score(&self, draw: &[BingoNumber]) -> Score {
-        let winning_number = draw.last().or(Some(&0)).unwrap();
+        let winning_number = draw.last().unwrap_or(&0);
         let sum_of_remaining: u32 = self.numbers.iter().filter(|x| !draw.contains(x)).sum();
         sum_of_remaining * winning_number
     }
 }
 
-type Score = u32;
-type BingoNumber = u32;
-
-pub fn print_result(input: impl std::io::Read) -> Result<()> {
-    let (draw, boards) = parse_bingo_input(input)?;
+pub type Score = u32;
+pub type BingoNumber = u32;
 
-    let (winning, losing) = get_winning_and_losing_board(boards, draw.as_slice());
-    if let Some(winning) = winning {
-        let (score, draw) = winning.score(draw.as_slice()).unwrap();
-        println!("Bingo board won on draw {} with score {}", draw, score);
-    }
-    if let Some(losing) = losing {
-        let (score, draw) = losing.score(draw.as_slice()).unwrap();
-        println!("Bingo board lost on draw {} with score {}", draw, score);
-    }
-    Ok(())
-}
-
-fn get_winning_and_losing_board(
+/// get_winning_and_losing_board returns the first and the last board to get bingo.
+pub fn get_winning_and_losing_board(
     mut boards: Vec<BingoBoard>,
     draw: &[BingoNumber],
 ) -> (Option<BingoBoard>, Option<BingoBoard>) {
@@ -138,7 +134,7 @@ fn get_winning_and_losing_board(
                 true
             }
         });
-        if boards.len() == 0 {
+        if boards.is_empty() {
             break;
         }
     }
@@ -146,46 +142,42 @@ fn get_winning_and_losing_board(
     (winning, losing)
 }
 
-fn parse_bingo_input(input: impl std::io::Read) -> Result<(Vec<BingoNumber>, Vec<BingoBoard>)> {
-    let lines = input::get_input_lines(input)?;
-    let mut iter = lines.iter().map(|s| s.as_str());
-    // First line is the draw, followed by new line.
-    let draw_line = iter.next().ok_or(Error::msg("error"))?;
-    let draw_numbers: Vec<BingoNumber> = draw_line
-        .split(",")
-        .filter_map(|n| n.parse().ok())
-        .collect();
-
-    let mut boards: Vec<BingoBoard> = vec![];
-    loop {
-        let numbers = parse_board_numbers(&mut iter)?;
-        if numbers == None {
-            break;
-        }
-        let numbers = numbers.unwrap();
-        let board = BingoBoard { numbers };
-        boards.push(board);
-    }
-
-    Ok((draw_numbers, boards))
+/// parse_bingo_input parses the numbers to draw and the bingo boards.
+pub fn parse_bingo_input(input: impl std::io::Read) -> Result<(Vec<BingoNumber>, Vec<BingoBoard>)> {
+    let mut sections = input::get_input_sections(input)?.into_iter();
+    // First section is the draw, followed by the boards.
+    let draw_line = match sections.next().as_deref() {
+        Some([line]) => line.numbers(line.field(), ",")?,
+        Some([_, line, ..]) => return Err(line.error(line.field(), "expected a board").into()),
+        _ => return Err(ParseError::new(1, 1, "", "empty input").into()),
+    };
+
+    let boards = sections
+        .map(|section| parse_board(&section))
+        .collect::<Result<_, _>>()?;
+
+    Ok((draw_line, boards))
 }
 
-fn parse_board_numbers<'a>(
-    lines: &mut impl Iterator<Item = &'a str>,
-) -> Result<Option<Vec<BingoNumber>>> {
-    let board_lines = lines.skip(1).take(5);
-    let numbers: Vec<BingoNumber> = board_lines
-        .map(|s| s.split(" "))
-        .flatten()
-        .filter_map(|x| x.parse().ok())
-        .collect();
-
-    if numbers.len() == 0 {
-        return Ok(None);
+fn parse_board(lines: &[NumberedLine]) -> Result<BingoBoard, ParseError> {
+    let mut numbers: Vec<BingoNumber> = vec![];
+    for line in lines {
+        let row: Vec<BingoNumber> = line.numbers(line.field(), " ")?;
+        if row.len() != BingoBoard::SIZE {
+            return Err(line.error(
+                line.field(),
+                format!("expected {} numbers in a board row", BingoBoard::SIZE),
+            ));
+        }
+        numbers.extend(row);
     }
-    if numbers.len() != BingoBoard::SIZE * BingoBoard::SIZE {
-        return Err(anyhow::Error::msg("error parsing number in bingo board"));
+    if lines.len() != BingoBoard::SIZE {
+        let line = &lines[lines.len().min(BingoBoard::SIZE) - 1];
+        return Err(line.error(
+            line.field(),
+            format!("expected {} rows in a board", BingoBoard::SIZE),
+        ));
     }
 
-    Ok(Some(numbers))
+    Ok(BingoBoard { numbers })
 }
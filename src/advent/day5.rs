@@ -1,128 +1,105 @@
 use std::collections::hash_map::HashMap;
-use std::str::FromStr;
 
 use anyhow::Result;
 
-use super::input;
+use super::geometry::Point2;
+use super::input::{self, Field, NumberedLine, ParseError};
+use super::{Answer, Solution};
 
-#[derive(Clone, Debug, Hash, Eq, PartialEq)]
-struct Point {
-    x: i32,
-    y: i32,
-}
+/// Solver solves the day 5 puzzle.
+pub struct Solver;
 
-impl std::str::FromStr for Point {
-    type Err = anyhow::Error;
+impl Solution for Solver {
+    const DAY: u8 = 5;
+    const TITLE: &'static str = "Hydrothermal Venture";
 
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        // "x,y"
-        let (x, y) = s.split_once(",").ok_or(anyhow::Error::msg("bad point"))?;
-        Ok(Point {
-            x: x.parse()?,
-            y: y.parse()?,
-        })
+    type Input = Vec<Line>;
+
+    fn parse(&self, input: impl std::io::Read) -> Result<Self::Input> {
+        let lines = input::get_numbered_lines(input)?
+            .iter()
+            .filter(|line| !line.is_blank())
+            .map(Line::parse)
+            .collect::<Result<_, _>>()?;
+        Ok(lines)
+    }
+
+    fn part1(&self, lines: &Self::Input) -> Result<Answer> {
+        let lines = lines.iter().filter(|l| l.horizontal() || l.vertical());
+        Ok(count_overlapping_points(lines).into())
     }
+
+    fn part2(&self, lines: &Self::Input) -> Result<Answer> {
+        let lines = lines
+            .iter()
+            .filter(|l| l.horizontal() || l.vertical() || l.diagonal());
+        Ok(count_overlapping_points(lines).into())
+    }
+}
+
+/// Point is a position on the ocean floor.
+pub type Point = Point2<i32>;
+
+/// parse_point parses `x,y`, a field of the line `line`.
+fn parse_point(line: &NumberedLine, s: Field) -> Result<Point, ParseError> {
+    let (x, y) = s
+        .split_once(",")
+        .ok_or_else(|| line.error(s, "expected 'x,y'"))?;
+    Ok(Point::new(line.parse(x)?, line.parse(y)?))
 }
 
+/// Line is a line of hydrothermal vents, parsed from `x1,y1 -> x2,y2`.
 #[derive(Clone, Debug)]
-struct Line(Point, Point);
+pub struct Line(Point, Point);
 
 impl std::str::FromStr for Line {
-    type Err = anyhow::Error;
+    type Err = ParseError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        // "x,y -> x,y"
-        let (s1, s2) = s.split_once(" -> ").ok_or(anyhow::Error::msg("bad line"))?;
-        Ok(Self(Point::from_str(s1)?, Point::from_str(s2)?))
+        Line::parse(&NumberedLine {
+            number: 1,
+            text: s.to_owned(),
+        })
     }
 }
 
 impl Line {
-    fn horizontal(&self) -> bool {
+    /// parse parses a line of vents from a line of input.
+    pub fn parse(line: &NumberedLine) -> Result<Self, ParseError> {
+        let (s1, s2) = line.rule()?;
+        Ok(Self(parse_point(line, s1)?, parse_point(line, s2)?))
+    }
+
+    pub fn horizontal(&self) -> bool {
         self.0.x == self.1.x
     }
 
-    fn vertical(&self) -> bool {
+    pub fn vertical(&self) -> bool {
         self.0.y == self.1.y
     }
 
-    fn diagonal(&self) -> bool {
+    pub fn diagonal(&self) -> bool {
         // a (45-deg.) diagonal line would have the same (absolute) difference
         // between the two points' x-coordinates as the y-coordinates.
-        let x_diff = (self.0.x - self.1.x).abs();
-        let y_diff = (self.0.y - self.1.y).abs();
-        x_diff == y_diff
-    }
-
-    /// iter is only correct for vertical or horizontal lines
-    fn iter(&self) -> impl Iterator<Item = Point> {
-        let mut current_step = self.point_count();
-        let line = self.clone();
-        std::iter::from_fn(move || {
-            if current_step == 0 {
-                None
-            } else {
-                current_step -= 1;
-                Some(line.point_at(current_step))
-            }
-        })
+        let d = self.1 - self.0;
+        d.x.abs() == d.y.abs()
     }
 
-    fn point_count(&self) -> u32 {
-        let x_diff = (self.0.x - self.1.x).abs() as u32;
-        let y_diff = (self.0.y - self.1.y).abs() as u32;
-        if x_diff >= y_diff {
-            x_diff + 1
-        } else {
-            y_diff + 1
-        }
-    }
-
-    fn point_at(&self, i: u32) -> Point {
-        let v_x = if self.0.x < self.1.x {
-            1
-        } else if self.0.x > self.1.x {
-            -1
-        } else {
-            0
-        };
-        let v_y: i32 = if self.0.y < self.1.y {
-            1
-        } else if self.0.y > self.1.y {
-            -1
-        } else {
-            0
-        };
-        let i = i as i32;
-        Point {
-            x: self.0.x + (v_x * i),
-            y: self.0.y + (v_y * i),
-        }
+    /// iter is only correct for vertical, horizontal or diagonal lines
+    pub fn iter(&self) -> impl Iterator<Item = Point> {
+        let (start, step) = (self.0, (self.1 - self.0).signum());
+        (0..=self.0.chebyshev(self.1)).map(move |i| start + step * i)
     }
 }
 
-pub fn print_overlapping_lines(input: impl std::io::Read) -> Result<()> {
-    let input_lines = input::get_input_lines(input)?;
-    // We assume all lines parse ok.
-    let lines = input_lines
-        .iter()
-        .map(|line_string| Line::from_str(line_string.as_str()))
-        .flatten()
-        .filter(|l| l.horizontal() || l.vertical() || l.diagonal());
-
-    let all_line_points = lines.map(|l| l.iter()).flatten();
+/// count_overlapping_points returns the number of points where at least two lines overlap.
+pub fn count_overlapping_points<'a>(lines: impl Iterator<Item = &'a Line>) -> usize {
+    let all_line_points = lines.flat_map(|l| l.iter());
     let mut point_overlap: HashMap<Point, u32> = HashMap::new();
     for point in all_line_points {
         // tabulate in hashmap
         let count = point_overlap.entry(point).or_insert(0);
         *count += 1;
     }
-    let n_points_with_overlap = point_overlap.iter().filter(|(_, &n)| n >= 2).count();
-
-    println!(
-        "Number of lines overlapping a single point is {}",
-        n_points_with_overlap
-    );
-
-    Ok(())
+    point_overlap.iter().filter(|(_, &n)| n >= 2).count()
 }
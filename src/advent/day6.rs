@@ -1,36 +1,48 @@
 use anyhow::Result;
 
 use super::input;
+use super::{Answer, Solution};
 
-pub fn print_fish_count_after_80_days(input: impl std::io::Read) -> Result<()> {
-    let input_lines = input::get_input_lines(input)?;
+/// Solver solves the day 6 puzzle.
+pub struct Solver;
 
-    let fish = input_lines
-        .get(0)
-        .ok_or(anyhow::Error::msg("bad input"))?
-        .split(",")
-        .map(|s| {
-            s.parse()
-                .map_err(|err| anyhow::Error::from(err).context(s.to_owned()))
-        })
-        .collect::<Result<Vec<u32>>>()?;
+impl Solution for Solver {
+    const DAY: u8 = 6;
+    const TITLE: &'static str = "Lanternfish";
 
-    let mut fish_partitions = partition_by_age(fish.clone());
+    type Input = Vec<u32>;
 
-    let mut fish_iter = fish_over_time_iter(fish_partitions.as_mut_slice());
-    println!(
-        "Number of fish ofter 80 days = {}",
-        fish_iter.nth(80).expect("working iterator")
-    );
+    fn parse(&self, input: impl std::io::Read) -> Result<Self::Input> {
+        let line = input::get_input_line(input)?;
+        let fish = line
+            .field()
+            .split(",")
+            .map(|s| {
+                let age = line.parse(s)?;
+                if age > 8 {
+                    return Err(line.error(s, "age is above 8"));
+                }
+                Ok(age)
+            })
+            .collect::<Result<_, _>>()?;
+        Ok(fish)
+    }
+
+    fn part1(&self, fish: &Self::Input) -> Result<Answer> {
+        Ok(fish_count_after(fish.clone(), 80).into())
+    }
+
+    fn part2(&self, fish: &Self::Input) -> Result<Answer> {
+        Ok(fish_count_after(fish.clone(), 256).into())
+    }
+}
+
+/// fish_count_after returns the number of lanternfish after the given number of days.
+pub fn fish_count_after(fish: Vec<u32>, days: usize) -> u64 {
     let mut fish_partitions = partition_by_age(fish);
 
     let mut fish_iter = fish_over_time_iter(fish_partitions.as_mut_slice());
-    println!(
-        "Number of fish ofter 256 days = {}",
-        fish_iter.nth(256).expect("working iterator")
-    );
-
-    Ok(())
+    fish_iter.nth(days).expect("working iterator")
 }
 
 fn partition_by_age(fish: Vec<u32>) -> Vec<u64> {
@@ -43,17 +55,15 @@ fn partition_by_age(fish: Vec<u32>) -> Vec<u64> {
 
 fn moar_fish(fish_partitions: &mut [u64]) {
     let spawning = fish_partitions[0];
-    for time_to_spawn in 0..8 {
-        fish_partitions[time_to_spawn] = fish_partitions[time_to_spawn + 1];
-    }
-    fish_partitions[6] = fish_partitions[6] + spawning;
+    fish_partitions.copy_within(1..9, 0);
+    fish_partitions[6] += spawning;
     fish_partitions[8] = spawning;
 }
 
-fn fish_over_time_iter<'a>(mut fish_partitions: &'a mut [u64]) -> impl Iterator<Item = u64> + 'a {
+fn fish_over_time_iter(fish_partitions: &mut [u64]) -> impl Iterator<Item = u64> + '_ {
     std::iter::from_fn(move || {
         let sum = fish_partitions.iter().sum();
-        moar_fish(&mut fish_partitions);
+        moar_fish(fish_partitions);
         Some(sum)
     })
 }
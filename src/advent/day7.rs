@@ -1,57 +1,48 @@
 use anyhow::Result;
 
 use super::input;
+use super::{Answer, Solution};
 
-pub fn print_optimal_position_and_fuel_requirement(input: impl std::io::Read) -> Result<()> {
-    let input_lines = input::get_input_lines(input)?;
+/// Solver solves the day 7 puzzle.
+pub struct Solver;
 
-    let positions = input_lines
-        .get(0)
-        .ok_or(anyhow::Error::msg("bad input"))?
-        .split(",")
-        .map(|s| {
-            s.parse()
-                .map_err(|err| anyhow::Error::from(err).context(s.to_owned()))
-        })
-        .collect::<Result<Vec<i32>>>()?;
+impl Solution for Solver {
+    const DAY: u8 = 7;
+    const TITLE: &'static str = "The Treachery of Whales";
 
-    let lowest_cost_naive = lowest_cost(positions.as_slice(), |c| c);
-    let lowest_cost_triangular = lowest_cost(positions.as_slice(), cost_to_move);
+    type Input = Vec<i32>;
 
-    println!(
-        "Part 1: Best position = {}, Total Fuel Cost = {}",
-        lowest_cost_naive.0, lowest_cost_naive.1,
-    );
+    fn parse(&self, input: impl std::io::Read) -> Result<Self::Input> {
+        input::get_input_number_list(input, ",")
+    }
 
-    // part 2
-    println!(
-        "Part 2: Best position = {}, Total Fuel Cost = {}",
-        lowest_cost_triangular.0, lowest_cost_triangular.1,
-    );
+    fn part1(&self, positions: &Self::Input) -> Result<Answer> {
+        // total fuel cost of the best position
+        let (_position, cost) = lowest_cost(positions.as_slice(), |c| c)
+            .ok_or_else(|| anyhow::Error::msg("no crab positions"))?;
+        Ok(cost.into())
+    }
 
-    Ok(())
+    fn part2(&self, positions: &Self::Input) -> Result<Answer> {
+        let (_position, cost) = lowest_cost(positions.as_slice(), cost_to_move)
+            .ok_or_else(|| anyhow::Error::msg("no crab positions"))?;
+        Ok(cost.into())
+    }
 }
 
-fn lowest_cost(positions: &[i32], cost_calc_func: impl Fn(i32) -> i32 + Copy) -> (i32, i32) {
-    if positions.len() == 0 {
-        panic!("positions slice is empty")
-    }
+/// lowest_cost returns the position the crabs can align to at the lowest total cost, and
+/// that cost, given the cost of moving a single crab a number of steps. It returns None if
+/// there are no crabs.
+pub(crate) fn lowest_cost(
+    positions: &[i32],
+    cost_calc_func: impl Fn(i32) -> i32 + Copy,
+) -> Option<(i32, i32)> {
     // the range relevant of positions
-    let range = positions.iter().min().unwrap().clone()..positions.iter().max().unwrap().clone();
-
-    let mut position_costs = range.map(|pos| (pos, sum_of_offsets(positions, pos, cost_calc_func)));
-    let first = position_costs.next().unwrap().clone();
+    let range = *positions.iter().min()?..=*positions.iter().max()?;
 
-    position_costs.fold(
-        first,
-        |lowest, cost| {
-            if cost.1 < lowest.1 {
-                cost
-            } else {
-                lowest
-            }
-        },
-    )
+    range
+        .map(|pos| (pos, sum_of_offsets(positions, pos, cost_calc_func)))
+        .min_by_key(|&(_, cost)| cost)
 }
 
 fn sum_of_offsets(positions: &[i32], target: i32, cost_calc: impl Fn(i32) -> i32) -> i32 {
@@ -61,7 +52,8 @@ fn sum_of_offsets(positions: &[i32], target: i32, cost_calc: impl Fn(i32) -> i32
         .sum()
 }
 
-fn cost_to_move(steps: i32) -> i32 {
+/// cost_to_move is the fuel cost of moving a crab with a crab engine.
+pub(crate) fn cost_to_move(steps: i32) -> i32 {
     // (1..=steps).sum()
     steps * (steps + 1) / 2
 }
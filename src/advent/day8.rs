@@ -2,60 +2,93 @@ use std::collections::HashMap;
 
 use anyhow::Result;
 
-use super::input;
+use super::input::{self, Field, ParseError};
+use super::{Answer, Solution};
 
-pub fn print_result(input: impl std::io::Read) -> Result<()> {
-    let input_lines = input::get_input_lines(input)?;
+/// Solver solves the day 8 puzzle.
+pub struct Solver;
 
-    let result_part_1 = input_lines
-        .iter()
-        .filter_map(|line| line.split_once(" | "))
-        .map(|(_input_values, output_values)| {
-            output_values
-                .split_terminator(" ")
-                .filter_map(|s| match s.len() {
-                    i @ 7 | i @ 4 | i @ 3 | i @ 2 => Some(i),
-                    _ => None,
-                })
-        })
-        .flatten()
-        .count();
+impl Solution for Solver {
+    const DAY: u8 = 8;
+    const TITLE: &'static str = "Seven Segment Search";
 
-    // part 1
-    println!("Part 1: {}", result_part_1);
+    type Input = Vec<String>;
 
-    //
-
-    let mut sum = 0;
-    for (i, line) in input_lines.iter().enumerate() {
-        println!("line {}", i);
-        let (signals, outputs) = line.split_once(" | ").unwrap();
-        let signals = signals.split_terminator(" ").collect::<Vec<&str>>();
-        let mut s = Sleuth {
-            known: [None; 10],
-            buf: ['\0'; 7],
-        };
-        let mapping = s.infer(signals);
-
-        let mut acc = 0;
-        let mut mul = 1000 as i64;
-        for word in outputs.split(" ") {
-            let mut mapped_word = String::new();
-            for c in word.chars() {
-                mapped_word.push(*mapping.get(&c).unwrap())
+    fn parse(&self, input: impl std::io::Read) -> Result<Self::Input> {
+        let mut lines = input::get_input_lines(input)?;
+        for (i, line) in lines.iter().enumerate() {
+            if line.trim().is_empty() {
+                continue;
+            }
+            if !line.contains(" | ") {
+                return Err(
+                    ParseError::in_line(i + 1, line, Field::new(line), "expected ' | '").into(),
+                );
             }
+            input::check_chars(
+                i + 1,
+                line,
+                |c| ('a'..='g').contains(&c) || c == ' ' || c == '|',
+                "bad segment",
+            )?;
+        }
+        lines.retain(|l| !l.trim().is_empty());
+        Ok(lines)
+    }
 
-            println!("Mapped: {} = {}", word, mapped_word.as_str());
-            // abcefg
-            acc += to_number(mapped_word.as_str()).unwrap() as i64 * mul;
-            mul /= 10;
+    fn part1(&self, input_lines: &Self::Input) -> Result<Answer> {
+        let count = input_lines
+            .iter()
+            .filter_map(|line| line.split_once(" | "))
+            .flat_map(|(_input_values, output_values)| {
+                output_values
+                    .split_terminator(' ')
+                    .filter(|s| matches!(s.len(), 7 | 4 | 3 | 2))
+            })
+            .count();
+        Ok(count.into())
+    }
+
+    fn part2(&self, input_lines: &Self::Input) -> Result<Answer> {
+        let mut sum = 0;
+        for line in input_lines.iter() {
+            sum += decode_output(line)?;
         }
-        println!("{} = {}", outputs, acc);
-        sum += acc;
+        Ok(sum.into())
     }
-    println!("Sum = {}", sum);
+}
 
-    Ok(())
+/// decode_output deduces the wiring of a `signals | outputs` note and returns the
+/// four digit output value.
+pub fn decode_output(line: &str) -> Result<i64> {
+    let (signals, outputs) = line
+        .split_once(" | ")
+        .ok_or_else(|| anyhow::Error::msg("malformed input"))?;
+    let signals = signals.split_terminator(' ').collect::<Vec<&str>>();
+    let mut s = Sleuth {
+        known: [None; 10],
+        buf: ['\0'; 7],
+    };
+    let mapping = s.infer(signals);
+
+    let mut acc = 0;
+    let mut mul = 1000i64;
+    for word in outputs.split(' ') {
+        let mut mapped_word = String::new();
+        for c in word.chars() {
+            let c = mapping
+                .get(&c)
+                .ok_or_else(|| anyhow::format_err!("unmapped segment '{}'", c))?;
+            mapped_word.push(*c)
+        }
+
+        // abcefg
+        let n = to_number(mapped_word.as_str())
+            .ok_or_else(|| anyhow::format_err!("bad digit '{}'", word))?;
+        acc += n as i64 * mul;
+        mul /= 10;
+    }
+    Ok(acc)
 }
 
 struct Sleuth<'a> {
@@ -225,38 +258,6 @@ impl<'a> Sleuth<'a> {
     }
 }
 
-fn signal_to_number(sig: &str) -> Option<u8> {
-    match sig {
-        "abcefg" => Some(0),
-        "cf" => Some(1),
-        "acdeg" => Some(2),
-        "acdfg" => Some(3),
-        "bcdf" => Some(4),
-        "abdfg" => Some(5),
-        "abdefg" => Some(6),
-        "acf" => Some(7),
-        "abcdefg" => Some(8),
-        "abcdfg" => Some(9),
-        _ => None,
-    }
-}
-
-fn number_to_signal(n: u8) -> Option<&'static str> {
-    match n {
-        0 => Some("abcefg"),
-        1 => Some("cf"),
-        2 => Some("acdeg"),
-        3 => Some("acdfg"),
-        4 => Some("bcdf"),
-        5 => Some("abdfg"),
-        6 => Some("abdefg"),
-        7 => Some("acf"),
-        8 => Some("abcdefg"),
-        9 => Some("abcdfg"),
-        _ => None,
-    }
-}
-
 /// CharSet is a set consisting of characters.
 /// Allows for checking equality of two &str's without sorting.
 #[derive(Debug, Eq)]
@@ -302,6 +303,7 @@ fn to_number(s: &str) -> Option<u8> {
 }
 
 #[test]
+#[allow(clippy::redundant_guards)]
 fn test_charset_eq() {
     assert!(CharSet("dbagf") == CharSet("abdfg")); // OK!
 
@@ -0,0 +1,319 @@
+//! Points and vectors in two and three dimensions, such as the vent lines of day 5, the
+//! dots of day 13, the probe of day 17 and the beacons of day 19.
+//!
+//! A point doubles as the vector between two points, so `b - a` is the offset from `a` to
+//! `b` and `a + (b - a) == b`.
+
+use std::fmt::Display;
+use std::ops::{Add, AddAssign, Mul, Neg, Sub, SubAssign};
+
+/// Coord is a signed integer usable as a coordinate.
+pub trait Coord:
+    Copy
+    + Ord
+    + Default
+    + Display
+    + Add<Output = Self>
+    + Sub<Output = Self>
+    + Mul<Output = Self>
+    + Neg<Output = Self>
+{
+    const ZERO: Self;
+    const ONE: Self;
+
+    fn abs(self) -> Self;
+    fn signum(self) -> Self;
+}
+
+macro_rules! impl_coord {
+    ($($t:ty),*) => {
+        $(
+            impl Coord for $t {
+                const ZERO: Self = 0;
+                const ONE: Self = 1;
+
+                #[inline]
+                fn abs(self) -> Self {
+                    <$t>::abs(self)
+                }
+
+                #[inline]
+                fn signum(self) -> Self {
+                    <$t>::signum(self)
+                }
+            }
+        )*
+    };
+}
+
+impl_coord!(i8, i16, i32, i64, i128, isize);
+
+/// Point2 is a point (or vector) in the plane.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Point2<T> {
+    pub x: T,
+    pub y: T,
+}
+
+/// Point3 is a point (or vector) in space.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Point3<T> {
+    pub x: T,
+    pub y: T,
+    pub z: T,
+}
+
+impl<T: Coord> Point2<T> {
+    pub const fn new(x: T, y: T) -> Self {
+        Self { x, y }
+    }
+
+    /// signum returns the vector with each component replaced by its sign, i.e. a single
+    /// step along a horizontal, vertical or diagonal vector.
+    pub fn signum(self) -> Self {
+        Self::new(self.x.signum(), self.y.signum())
+    }
+
+    /// manhattan is the taxicab distance to `other`.
+    pub fn manhattan(self, other: Self) -> T {
+        let d = other - self;
+        d.x.abs() + d.y.abs()
+    }
+
+    /// chebyshev is the number of king's moves to `other`.
+    pub fn chebyshev(self, other: Self) -> T {
+        let d = other - self;
+        std::cmp::max(d.x.abs(), d.y.abs())
+    }
+}
+
+impl<T: Coord> Point3<T> {
+    pub const fn new(x: T, y: T, z: T) -> Self {
+        Self { x, y, z }
+    }
+
+    /// signum returns the vector with each component replaced by its sign.
+    pub fn signum(self) -> Self {
+        Self::new(self.x.signum(), self.y.signum(), self.z.signum())
+    }
+
+    /// manhattan is the taxicab distance to `other`.
+    pub fn manhattan(self, other: Self) -> T {
+        let d = other - self;
+        d.x.abs() + d.y.abs() + d.z.abs()
+    }
+
+    /// chebyshev is the largest distance to `other` along any one axis.
+    pub fn chebyshev(self, other: Self) -> T {
+        let d = other - self;
+        std::cmp::max(std::cmp::max(d.x.abs(), d.y.abs()), d.z.abs())
+    }
+}
+
+/// impl_ops implements the component-wise operators of a point type.
+macro_rules! impl_ops {
+    ($point:ident { $($c:ident),* }) => {
+        impl<T: Coord> Add for $point<T> {
+            type Output = Self;
+
+            fn add(self, rhs: Self) -> Self {
+                Self { $($c: self.$c + rhs.$c),* }
+            }
+        }
+
+        impl<T: Coord> Sub for $point<T> {
+            type Output = Self;
+
+            fn sub(self, rhs: Self) -> Self {
+                Self { $($c: self.$c - rhs.$c),* }
+            }
+        }
+
+        impl<T: Coord> Neg for $point<T> {
+            type Output = Self;
+
+            fn neg(self) -> Self {
+                Self { $($c: -self.$c),* }
+            }
+        }
+
+        /// Scaling by a scalar.
+        impl<T: Coord> Mul<T> for $point<T> {
+            type Output = Self;
+
+            fn mul(self, rhs: T) -> Self {
+                Self { $($c: self.$c * rhs),* }
+            }
+        }
+
+        impl<T: Coord> AddAssign for $point<T> {
+            fn add_assign(&mut self, rhs: Self) {
+                *self = *self + rhs;
+            }
+        }
+
+        impl<T: Coord> SubAssign for $point<T> {
+            fn sub_assign(&mut self, rhs: Self) {
+                *self = *self - rhs;
+            }
+        }
+
+        impl<T: Coord> Extent for $point<T> {
+            fn min_each(self, other: Self) -> Self {
+                Self { $($c: std::cmp::min(self.$c, other.$c)),* }
+            }
+
+            fn max_each(self, other: Self) -> Self {
+                Self { $($c: std::cmp::max(self.$c, other.$c)),* }
+            }
+
+            fn le_each(self, other: Self) -> bool {
+                true $(&& self.$c <= other.$c)*
+            }
+        }
+    };
+}
+
+impl_ops!(Point2 { x, y });
+impl_ops!(Point3 { x, y, z });
+
+impl<T: Display> Display for Point2<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{},{}", self.x, self.y)
+    }
+}
+
+impl<T: Display> Display for Point3<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{},{},{}", self.x, self.y, self.z)
+    }
+}
+
+impl<T> From<(T, T)> for Point2<T> {
+    fn from((x, y): (T, T)) -> Self {
+        Self { x, y }
+    }
+}
+
+impl<T> From<Point2<T>> for (T, T) {
+    fn from(p: Point2<T>) -> Self {
+        (p.x, p.y)
+    }
+}
+
+impl<T> From<[T; 2]> for Point2<T> {
+    fn from([x, y]: [T; 2]) -> Self {
+        Self { x, y }
+    }
+}
+
+impl<T> From<(T, T, T)> for Point3<T> {
+    fn from((x, y, z): (T, T, T)) -> Self {
+        Self { x, y, z }
+    }
+}
+
+impl<T> From<[T; 3]> for Point3<T> {
+    fn from([x, y, z]: [T; 3]) -> Self {
+        Self { x, y, z }
+    }
+}
+
+impl<T> From<Point3<T>> for [T; 3] {
+    fn from(p: Point3<T>) -> Self {
+        [p.x, p.y, p.z]
+    }
+}
+
+/// Extent is implemented by points, comparing and combining them one component at a time.
+pub trait Extent: Copy {
+    /// min_each is the point with the smallest of each component.
+    fn min_each(self, other: Self) -> Self;
+    /// max_each is the point with the largest of each component.
+    fn max_each(self, other: Self) -> Self;
+    /// le_each is true if every component is less than or equal to that of `other`.
+    fn le_each(self, other: Self) -> bool;
+}
+
+/// BoundingBox is the smallest box containing a set of points, including its edges.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct BoundingBox<P> {
+    pub min: P,
+    pub max: P,
+}
+
+impl<P: Extent> BoundingBox<P> {
+    /// new creates the bounding box with any two opposite corners `a` and `b`.
+    pub fn new(a: P, b: P) -> Self {
+        Self {
+            min: a.min_each(b),
+            max: a.max_each(b),
+        }
+    }
+
+    /// from_points returns the bounding box of `points`, or None if there are none.
+    pub fn from_points(points: impl IntoIterator<Item = P>) -> Option<Self> {
+        let mut points = points.into_iter();
+        let first = points.next()?;
+        let mut bounds = Self::new(first, first);
+        points.for_each(|p| bounds.include(p));
+        Some(bounds)
+    }
+
+    /// include grows the box to contain `p`.
+    pub fn include(&mut self, p: P) {
+        self.min = self.min.min_each(p);
+        self.max = self.max.max_each(p);
+    }
+
+    /// contains is true if `p` is inside the box or on its edge.
+    pub fn contains(&self, p: P) -> bool {
+        self.min.le_each(p) && p.le_each(self.max)
+    }
+}
+
+impl<T: Coord> BoundingBox<Point2<T>> {
+    /// width is the number of columns of the box.
+    pub fn width(&self) -> T {
+        self.max.x - self.min.x + T::ONE
+    }
+
+    /// height is the number of rows of the box.
+    pub fn height(&self) -> T {
+        self.max.y - self.min.y + T::ONE
+    }
+}
+
+#[test]
+fn test_points() {
+    let a = Point2::new(1, -2);
+    let b: Point2<i32> = (4, 2).into();
+    assert_eq!(b - a, Point2::new(3, 4));
+    assert_eq!(a + (b - a), b);
+    assert_eq!(-a * 2, Point2::new(-2, 4));
+    assert_eq!((b - a).signum(), Point2::new(1, 1));
+    assert_eq!(a.manhattan(b), 7);
+    assert_eq!(a.chebyshev(b), 4);
+
+    let mut c = Point3::from([1, 2, 3]);
+    c += Point3::new(-2, 0, 1);
+    assert_eq!(<[i64; 3]>::from(c), [-1, 2, 4]);
+    assert_eq!(c.manhattan(Point3::default()), 7);
+    assert_eq!(c.chebyshev(Point3::default()), 4);
+    assert_eq!(c.to_string(), "-1,2,4");
+}
+
+#[test]
+fn test_bounding_box() {
+    let bounds = BoundingBox::new(Point2::new(30, -5), Point2::new(20, -10));
+    assert_eq!(bounds.min, Point2::new(20, -10));
+    assert_eq!((bounds.width(), bounds.height()), (11, 6));
+    assert!(bounds.contains(Point2::new(20, -5)));
+    assert!(!bounds.contains(Point2::new(20, -4)));
+
+    let points = [Point3::new(1, 5, -1), Point3::new(-3, 2, 0)];
+    let bounds = BoundingBox::from_points(points.iter().copied()).unwrap();
+    assert_eq!(bounds.min, Point3::new(-3, 2, -1));
+    assert_eq!(bounds.max, Point3::new(1, 5, 0));
+    assert!(BoundingBox::<Point2<i32>>::from_points(vec![]).is_none());
+}
@@ -0,0 +1,543 @@
+//! A rectangular grid of values, such as the height maps and risk maps of days 9, 11
+//! and 15.
+//!
+//! Positions are `(x, y)` with `(0, 0)` at the top left, x growing to the right and y
+//! growing downwards.
+
+use std::fmt::Display;
+use std::ops::{Index, IndexMut};
+
+use anyhow::Result;
+
+use super::geometry::Point2;
+use super::input;
+
+/// Position is an `(x, y)` position in a grid.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Position(pub usize, pub usize);
+
+impl Position {
+    #[inline]
+    pub fn x(&self) -> usize {
+        self.0
+    }
+
+    #[inline]
+    pub fn y(&self) -> usize {
+        self.1
+    }
+
+    /// offset returns the position moved by `(dx, dy)`, or None if that is left of or
+    /// above the origin.
+    pub fn offset(&self, (dx, dy): (isize, isize)) -> Option<Position> {
+        let add = |v: usize, d: isize| {
+            if d < 0 {
+                v.checked_sub(d.unsigned_abs())
+            } else {
+                v.checked_add(d as usize)
+            }
+        };
+        Some(Position(add(self.0, dx)?, add(self.1, dy)?))
+    }
+}
+
+impl From<Position> for Point2<isize> {
+    fn from(Position(x, y): Position) -> Self {
+        Point2::new(x as isize, y as isize)
+    }
+}
+
+impl TryFrom<Point2<isize>> for Position {
+    type Error = std::num::TryFromIntError;
+
+    /// try_from fails for points left of or above the origin.
+    fn try_from(p: Point2<isize>) -> Result<Self, Self::Error> {
+        Ok(Position(usize::try_from(p.x)?, usize::try_from(p.y)?))
+    }
+}
+
+impl From<(usize, usize)> for Position {
+    fn from((x, y): (usize, usize)) -> Self {
+        Position(x, y)
+    }
+}
+
+/// NEIGHBOURS4 are the offsets of the right, bottom, left and top neighbours.
+pub const NEIGHBOURS4: [(isize, isize); 4] = [(1, 0), (0, 1), (-1, 0), (0, -1)];
+
+/// NEIGHBOURS8 are the offsets of the `NEIGHBOURS4`, followed by the diagonal neighbours.
+pub const NEIGHBOURS8: [(isize, isize); 8] = [
+    (1, 0),
+    (0, 1),
+    (-1, 0),
+    (0, -1),
+    (-1, -1),
+    (1, -1),
+    (-1, 1),
+    (1, 1),
+];
+
+/// Grid is a rectangular grid of values, stored in row order.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Grid<T> {
+    width: usize,
+    height: usize,
+    values: Vec<T>,
+}
+
+impl<T> Grid<T> {
+    /// new creates a grid from its values in row order, `width` values per row.
+    /// A trailing partial row is dropped.
+    pub fn new(mut values: Vec<T>, width: usize) -> Self {
+        let height = match width {
+            0 => 0,
+            _ => values.len() / width,
+        };
+        values.truncate(width * height);
+        Self {
+            width,
+            height,
+            values,
+        }
+    }
+
+    /// from_fn creates a grid with the value of every position given by `f`.
+    pub fn from_fn(width: usize, height: usize, mut f: impl FnMut(Position) -> T) -> Self {
+        let values = (0..height)
+            .flat_map(|y| (0..width).map(move |x| Position(x, y)))
+            .map(&mut f)
+            .collect();
+        Self {
+            width,
+            height,
+            values,
+        }
+    }
+
+    /// filled creates a grid with every value set to `value`.
+    pub fn filled(width: usize, height: usize, value: T) -> Self
+    where
+        T: Clone,
+    {
+        Self {
+            width,
+            height,
+            values: vec![value; width * height],
+        }
+    }
+
+    /// parse_with parses a grid with one character per value, skipping blank lines.
+    /// `cell` returns None for characters that aren't valid.
+    pub fn parse_with(
+        f: impl std::io::Read,
+        mut cell: impl FnMut(char) -> Option<T>,
+    ) -> Result<Self> {
+        let mut values = vec![];
+        let mut width = 0;
+        for line in input::get_numbered_lines(f)? {
+            let row = line.field().trim();
+            if row.text.is_empty() {
+                continue;
+            }
+            let len = row.text.chars().count();
+            if width != 0 && len != width {
+                return Err(line
+                    .error(row, format!("expected {} values in a row", width))
+                    .into());
+            }
+            width = len;
+            for (c, field) in row.chars() {
+                let value = cell(c).ok_or_else(|| line.error(field, "unexpected character"))?;
+                values.push(value);
+            }
+        }
+        Ok(Self::new(values, width))
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    /// max is the bottom right position of the grid.
+    pub fn max(&self) -> Position {
+        Position(self.width.saturating_sub(1), self.height.saturating_sub(1))
+    }
+
+    pub fn contains(&self, pos: Position) -> bool {
+        pos.0 < self.width && pos.1 < self.height
+    }
+
+    pub fn get(&self, pos: Position) -> Option<&T> {
+        self.values.get(self.index(pos)?)
+    }
+
+    pub fn get_mut(&mut self, pos: Position) -> Option<&mut T> {
+        let i = self.index(pos)?;
+        self.values.get_mut(i)
+    }
+
+    fn index(&self, pos: Position) -> Option<usize> {
+        if self.contains(pos) {
+            Some(pos.1 * self.width + pos.0)
+        } else {
+            None
+        }
+    }
+
+    /// positions iterates all positions in row order.
+    pub fn positions(&self) -> impl Iterator<Item = Position> {
+        let width = self.width;
+        (0..self.len()).map(move |i| Position(i % width, i / width))
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &T> {
+        self.values.iter()
+    }
+
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = &mut T> {
+        self.values.iter_mut()
+    }
+
+    /// enumerate iterates all positions and their values in row order.
+    pub fn enumerate(&self) -> impl Iterator<Item = (Position, &T)> {
+        self.positions().zip(self.values.iter())
+    }
+
+    pub fn row(&self, y: usize) -> Option<&[T]> {
+        if y < self.height {
+            Some(&self.values[y * self.width..(y + 1) * self.width])
+        } else {
+            None
+        }
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> + '_ {
+        (0..self.height).map(move |y| &self.values[y * self.width..(y + 1) * self.width])
+    }
+
+    /// column iterates the values of column `x` from top to bottom. It is empty if `x` is
+    /// out of bounds.
+    pub fn column(&self, x: usize) -> impl Iterator<Item = &T> + '_ {
+        let (skip, take) = if x < self.width {
+            (x, self.height)
+        } else {
+            (0, 0)
+        };
+        self.values
+            .iter()
+            .skip(skip)
+            .step_by(self.width.max(1))
+            .take(take)
+    }
+
+    pub fn columns(&self) -> impl Iterator<Item = impl Iterator<Item = &T> + '_> + '_ {
+        (0..self.width).map(move |x| self.column(x))
+    }
+
+    /// neighbours returns the positions at the given offsets from `pos` that are in the grid.
+    pub fn neighbours<'a>(
+        &self,
+        pos: Position,
+        offsets: &'a [(isize, isize)],
+    ) -> impl Iterator<Item = Position> + 'a {
+        let (width, height) = (self.width, self.height);
+        offsets
+            .iter()
+            .filter_map(move |&offset| pos.offset(offset))
+            .filter(move |p| p.0 < width && p.1 < height)
+    }
+
+    /// neighbours4 returns the right, bottom, left and top neighbours that are in the grid.
+    pub fn neighbours4(&self, pos: Position) -> impl Iterator<Item = Position> {
+        self.neighbours(pos, &NEIGHBOURS4)
+    }
+
+    /// neighbours8 returns the neighbours4 followed by the diagonal neighbours that are in
+    /// the grid.
+    pub fn neighbours8(&self, pos: Position) -> impl Iterator<Item = Position> {
+        self.neighbours(pos, &NEIGHBOURS8)
+    }
+
+    /// map returns a grid of the same size with `f` applied to every value.
+    pub fn map<U>(&self, f: impl FnMut(&T) -> U) -> Grid<U> {
+        Grid {
+            width: self.width,
+            height: self.height,
+            values: self.values.iter().map(f).collect(),
+        }
+    }
+
+    /// zip combines the values of two grids of the same size, or returns None if the
+    /// sizes differ.
+    pub fn zip<U, V>(&self, other: &Grid<U>, mut f: impl FnMut(&T, &U) -> V) -> Option<Grid<V>> {
+        if (self.width, self.height) != (other.width, other.height) {
+            return None;
+        }
+        Some(Grid {
+            width: self.width,
+            height: self.height,
+            values: self
+                .values
+                .iter()
+                .zip(other.values.iter())
+                .map(|(a, b)| f(a, b))
+                .collect(),
+        })
+    }
+
+    /// tile repeats the grid `tiles_x` times to the right and `tiles_y` times down. `f`
+    /// is given every value with the `(x, y)` index of the tile it is copied to.
+    pub fn tile<U>(
+        &self,
+        tiles_x: usize,
+        tiles_y: usize,
+        mut f: impl FnMut(&T, usize, usize) -> U,
+    ) -> Grid<U> {
+        Grid::from_fn(self.width * tiles_x, self.height * tiles_y, |pos| {
+            let value = &self[Position(pos.0 % self.width, pos.1 % self.height)];
+            f(value, pos.0 / self.width, pos.1 / self.height)
+        })
+    }
+
+    /// view returns the part of the grid of the given size with its top left at `origin`,
+    /// or None if it doesn't fit in the grid.
+    pub fn view(&self, origin: Position, width: usize, height: usize) -> Option<GridView<'_, T>> {
+        if origin.0 + width > self.width || origin.1 + height > self.height {
+            return None;
+        }
+        Some(GridView {
+            grid: self,
+            origin,
+            width,
+            height,
+        })
+    }
+
+    /// render returns the grid as text, one line per row, with `cell` giving the
+    /// character of every value.
+    pub fn render(&self, mut cell: impl FnMut(&T) -> char) -> String {
+        let mut s = String::with_capacity((self.width + 1) * self.height);
+        for row in self.rows() {
+            s.extend(row.iter().map(&mut cell));
+            s.push('\n');
+        }
+        s
+    }
+}
+
+impl<T> Grid<T>
+where
+    T: From<u8>,
+{
+    /// parse_digits parses a grid of single digits.
+    pub fn parse_digits(f: impl std::io::Read) -> Result<Self> {
+        Self::parse_with(f, |c| c.to_digit(10).map(|d| T::from(d as u8)))
+    }
+}
+
+impl<T> Index<Position> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, pos: Position) -> &T {
+        self.get(pos)
+            .unwrap_or_else(|| panic!("{:?} is outside of the grid", pos))
+    }
+}
+
+impl<T> IndexMut<Position> for Grid<T> {
+    fn index_mut(&mut self, pos: Position) -> &mut T {
+        self.get_mut(pos)
+            .unwrap_or_else(|| panic!("{:?} is outside of the grid", pos))
+    }
+}
+
+impl<T> Display for Grid<T>
+where
+    T: Display,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for row in self.rows() {
+            for value in row {
+                write!(f, "{}", value)?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+/// GridView is a rectangular part of a grid. Positions are relative to its top left.
+#[derive(Clone, Copy, Debug)]
+pub struct GridView<'a, T> {
+    grid: &'a Grid<T>,
+    origin: Position,
+    width: usize,
+    height: usize,
+}
+
+impl<'a, T> GridView<'a, T> {
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// origin is the position of the view's top left in the grid.
+    pub fn origin(&self) -> Position {
+        self.origin
+    }
+
+    pub fn get(&self, pos: Position) -> Option<&'a T> {
+        if pos.0 < self.width && pos.1 < self.height {
+            self.grid
+                .get(Position(self.origin.0 + pos.0, self.origin.1 + pos.1))
+        } else {
+            None
+        }
+    }
+
+    pub fn positions(&self) -> impl Iterator<Item = Position> {
+        let width = self.width;
+        (0..width * self.height).map(move |i| Position(i % width, i / width))
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = &'a [T]> {
+        let (grid, origin, width) = (self.grid, self.origin, self.width);
+        (origin.1..origin.1 + self.height).map(move |y| {
+            let row = grid.row(y).expect("view is inside the grid");
+            &row[origin.0..origin.0 + width]
+        })
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &'a T> {
+        self.rows().flatten()
+    }
+
+    /// to_grid copies the view into a grid of its own.
+    pub fn to_grid(&self) -> Grid<T>
+    where
+        T: Clone,
+    {
+        Grid::new(self.iter().cloned().collect(), self.width)
+    }
+}
+
+#[test]
+fn test_grid() {
+    let g = Grid::new(vec![0, 1, 2, 3, 4, 5], 3);
+    // 012
+    // 345
+    assert_eq!((g.width(), g.height(), g.max()), (3, 2, Position(2, 1)));
+    assert_eq!(g.positions().count(), 6);
+    assert_eq!(g.get(Position(0, 1)), Some(&3));
+    assert_eq!(g.get(Position(2, 1)), Some(&5));
+    assert_eq!(g.get(Position(3, 0)), None);
+    assert_eq!(g.get(Position(0, 2)), None);
+    assert_eq!(g.rows().collect::<Vec<_>>(), [[0, 1, 2], [3, 4, 5]]);
+    assert_eq!(g.column(1).copied().collect::<Vec<_>>(), [1, 4]);
+    assert_eq!(g.column(3).count(), 0);
+    assert_eq!(g.columns().count(), 3);
+
+    assert_eq!(
+        g.neighbours4(Position(0, 0)).collect::<Vec<_>>(),
+        [Position(1, 0), Position(0, 1)]
+    );
+    assert_eq!(g.neighbours8(Position(1, 0)).count(), 5);
+    assert_eq!(g.neighbours8(Position(1, 1)).count(), 5);
+
+    assert_eq!(Position(1, 2).offset((-1, 3)), Some(Position(0, 5)));
+    assert_eq!(Position(0, 2).offset((-1, 0)), None);
+    assert_eq!(Position(usize::MAX, 0).offset((1, 0)), None);
+    assert_eq!(
+        Position(usize::MAX, 0).offset((-1, 0)),
+        Some(Position(usize::MAX - 1, 0))
+    );
+    assert_eq!(Position(0, 0).offset((isize::MIN, 0)), None);
+}
+
+#[test]
+fn test_grid_index() {
+    let g = Grid::new(vec![0; 4], 2);
+    // 00
+    // 00
+    assert_eq!(g.positions().count(), 4);
+    assert_eq!(g.index(Position(0, 0)), Some(0));
+    assert_eq!(g.index(Position(1, 0)), Some(1));
+    assert_eq!(g.index(Position(0, 1)), Some(2));
+    assert_eq!(g.index(Position(1, 1)), Some(3));
+    assert_eq!(g.index(Position(2, 0)), None);
+    assert_eq!(g.index(Position(2, 1)), None);
+    assert_eq!(g.index(Position(1, 2)), None);
+
+    let g = Grid::new(vec![0, 0, 0, 1, 1, 1, 2, 2, 2], 3);
+    // 000
+    // 111
+    // 222
+    assert_eq!(g.positions().count(), 9);
+    for (i, pos) in g.positions().enumerate() {
+        assert_eq!(g.index(pos), Some(i));
+    }
+    assert_eq!(g.index(Position(2, 0)), Some(2));
+    assert_eq!(g.index(Position(0, 1)), Some(3));
+    assert_eq!(g.index(Position(2, 2)), Some(8));
+    for y in 0..3 {
+        assert_eq!(g.index(Position(3, y)), None);
+        assert_eq!(g.get(Position(3, y)), None);
+    }
+    assert_eq!(g.index(Position(3, 3)), None);
+    assert_eq!(g.index(Position(0, 3)), None);
+
+    let mut rows = g.rows();
+    assert_eq!(
+        [
+            rows.next().unwrap(),
+            rows.next().unwrap(),
+            rows.next().unwrap()
+        ],
+        [[0, 0, 0], [1, 1, 1], [2, 2, 2]]
+    );
+    assert_eq!(rows.next(), None);
+    assert_eq!(g.row(3), None);
+}
+
+#[test]
+fn test_grid_operations() {
+    let g: Grid<u8> = Grid::parse_digits("12\r\n34\n".as_bytes()).unwrap();
+    assert_eq!(g.to_string(), "12\n34\n");
+
+    let tiled = g.tile(2, 2, |&v, tx, ty| (v + (tx + ty) as u8 - 1) % 9 + 1);
+    assert_eq!(tiled.to_string(), "1223\n3445\n2334\n4556\n");
+    assert_eq!(
+        tiled.render(|&v| if v > 4 { '#' } else { '.' }),
+        "....\n...#\n....\n.###\n"
+    );
+
+    let view = tiled.view(Position(1, 1), 2, 2).unwrap();
+    assert_eq!(view.get(Position(0, 0)), Some(&4));
+    assert_eq!(view.get(Position(2, 0)), None);
+    assert_eq!(view.to_grid().to_string(), "44\n33\n");
+    assert!(tiled.view(Position(3, 3), 2, 2).is_none());
+
+    let doubled = g.map(|&v| v as u32 * 2);
+    let sum = g.zip(&doubled, |&a, &b| a as u32 + b).unwrap();
+    assert_eq!(sum.iter().copied().collect::<Vec<_>>(), [3, 6, 9, 12]);
+    assert!(g.zip(&tiled, |a, b| a + b).is_none());
+
+    let err = Grid::<u8>::parse_digits("12\n3x\n".as_bytes()).unwrap_err();
+    let err = err.downcast::<input::ParseError>().unwrap();
+    assert_eq!((err.line, err.column), (2, 2));
+}
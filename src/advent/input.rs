@@ -1,18 +1,109 @@
 // mod input
 
 use anyhow::Result;
+use std::fmt::Display;
 use std::fs::File;
 use std::io::BufRead;
-use std::num::ParseIntError;
+use std::ops::RangeInclusive;
 use std::path::Path;
+use std::str::FromStr;
 
+/// read_file opens a puzzle input file.
 pub fn read_file(file_path: impl AsRef<Path>) -> Result<impl std::io::Read> {
     Ok(File::open(file_path)?)
 }
 
+/// ParseError is an error in a puzzle input, with the location of the offending text.
+///
+/// Errors raised by the shared parsers in this module don't know which day they are
+/// parsing for; the day is filled in when the input is parsed through the registry.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    pub day: Option<u8>,
+    /// line is the 1-based line number of the offending text.
+    pub line: usize,
+    /// column is the 1-based column, in characters, where the offending text starts.
+    pub column: usize,
+    pub text: String,
+    pub reason: String,
+}
+
+impl ParseError {
+    pub fn new(line: usize, column: usize, text: &str, reason: impl Display) -> Self {
+        Self {
+            day: None,
+            line,
+            column,
+            text: text.to_owned(),
+            reason: reason.to_string(),
+        }
+    }
+
+    /// in_line creates an error for `field`, a part of the line `line`. The column is
+    /// where the field starts in the line.
+    pub fn in_line(line_number: usize, line: &str, field: Field, reason: impl Display) -> Self {
+        let column = match line.get(..field.offset) {
+            Some(before) => before.chars().count() + 1,
+            None => field.offset + 1,
+        };
+        Self::new(line_number, column, field.text, reason)
+    }
+
+    /// with_day sets the day the error was found in, unless it is already set.
+    pub fn with_day(mut self, day: u8) -> Self {
+        self.day.get_or_insert(day);
+        self
+    }
+}
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if let Some(day) = self.day {
+            write!(f, "day {}, ", day)?;
+        }
+        write!(
+            f,
+            "line {}, column {}: {}: '{}'",
+            self.line, self.column, self.reason, self.text
+        )
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+/// parse_in_line parses `field`, a part of the line `line`, returning a ParseError
+/// pointing at it if it can't be parsed.
+pub fn parse_in_line<T>(line_number: usize, line: &str, field: Field) -> Result<T, ParseError>
+where
+    T: FromStr,
+    T::Err: Display,
+{
+    field
+        .text
+        .parse()
+        .map_err(|err| ParseError::in_line(line_number, line, field, err))
+}
+
+/// check_chars returns a ParseError pointing at the first character of the line that
+/// isn't valid.
+pub fn check_chars(
+    line_number: usize,
+    line: &str,
+    valid: impl Fn(char) -> bool,
+    reason: impl Display,
+) -> Result<(), ParseError> {
+    match Field::new(line).chars().find(|&(c, _)| !valid(c)) {
+        Some((_, field)) => Err(ParseError::in_line(line_number, line, field, reason)),
+        None => Ok(()),
+    }
+}
+
+/// get_input_numbers parses one number per line, skipping blank lines. It also returns
+/// the length of the last line.
 pub fn get_input_numbers<T>(f: impl std::io::Read) -> Result<(Vec<T>, usize)>
 where
-    T: core::str::FromStr<Err = ParseIntError>,
+    T: FromStr,
+    T::Err: Display,
 {
     let buf = std::io::BufReader::new(f);
 
@@ -20,34 +111,50 @@ where
     // We do it this way to avoid unnecessay allocations.
     // We collect into a vec rather than keeping as an iterator so that we
     // don't have to defer IO error handling to later iterations of the data.
-    let numbers: (Vec<T>, usize) =
-        buf.lines()
-            .try_fold::<_, _, Result<_>>((vec![], 0usize), |(mut v, mut line_len), r| {
-                let numstring = r?;
-                let trimmed = numstring.trim();
-                if !trimmed.is_empty() {
-                    v.push(trimmed.parse::<T>()?);
-                    line_len = trimmed.len();
-                }
-                Ok((v, line_len))
-            })?;
+    let numbers: (Vec<T>, usize) = buf.lines().enumerate().try_fold::<_, _, Result<_>>(
+        (vec![], 0usize),
+        |(mut v, mut line_len), (i, r)| {
+            let numstring = r?;
+            let trimmed = numstring.trim();
+            if !trimmed.is_empty() {
+                v.push(parse_in_line(
+                    i + 1,
+                    &numstring,
+                    Field::new(&numstring).trim(),
+                )?);
+                line_len = trimmed.len();
+            }
+            Ok((v, line_len))
+        },
+    )?;
 
     Ok(numbers)
 }
 
-pub fn get_input_hex(mut f: impl std::io::Read) -> Result<Vec<u8>> {
-    let mut buf = String::new();
-    f.read_to_string(&mut buf)?;
-    let buf = buf.trim();
+/// get_input_hex decodes a hexadecimal input, a single line, into bytes.
+pub fn get_input_hex(f: impl std::io::Read) -> Result<Vec<u8>> {
+    let line = get_input_line(f)?;
+    let hex = line.field().trim();
+    if let Some((_, c)) = hex.chars().find(|&(c, _)| !c.is_ascii_hexdigit()) {
+        return Err(line.error(c, "bad hex").into());
+    }
+    let len = hex.text.len();
+    if len % 2 == 1 {
+        let last = hex.slice(len - 1..len);
+        return Err(line.error(last, "odd number of hex digits").into());
+    }
 
-    let v: Result<Vec<_>, std::num::ParseIntError> = (0..buf.len())
+    // Every character is a hex digit, so pairs of them always parse.
+    let v = (0..len)
         .step_by(2)
-        .map(|i| u8::from_str_radix(&buf[i..i + 2], 16))
+        .map(|i| u8::from_str_radix(&hex.text[i..i + 2], 16).unwrap())
         .collect();
 
-    v.map_err(|_e| anyhow::Error::msg("bad input"))
+    Ok(v)
 }
 
+/// get_input_number_grid parses a grid of single digits in row order, and returns the
+/// digits with the length of a row.
 pub fn get_input_number_grid<T>(f: impl std::io::Read) -> Result<(Vec<T>, usize)>
 where
     T: core::convert::From<u8>,
@@ -58,17 +165,30 @@ where
     // We do it this way to avoid unnecessay allocations.
     // We collect into a vec rather than keeping as an iterator so that we
     // don't have to defer IO error handling to later iterations of the data.
-    let numbers: (Vec<T>, usize) = buf.lines().try_fold::<_, _, Result<_>>(
+    let numbers: (Vec<T>, usize) = buf.lines().enumerate().try_fold::<_, _, Result<_>>(
         (vec![], 0usize),
-        |(mut v, mut line_len), line| {
+        |(mut v, mut line_len), (i, line)| {
             let line = line?;
-            let trimmed = line.trim();
-            if !trimmed.is_empty() {
-                line_len = trimmed.len();
-                for c in trimmed.chars() {
-                    match c as u8 {
-                        c @ 48..=59 => v.push((c - 48).into()),
-                        _ => return Err(anyhow::Error::msg("char is not a digit")),
+            let trimmed = Field::new(&line).trim();
+            if !trimmed.text.is_empty() {
+                if line_len != 0 && trimmed.text.len() != line_len {
+                    return Err(ParseError::in_line(
+                        i + 1,
+                        &line,
+                        trimmed,
+                        format!("expected {} digits", line_len),
+                    )
+                    .into());
+                }
+                line_len = trimmed.text.len();
+                for (c, field) in trimmed.chars() {
+                    match c.to_digit(10) {
+                        Some(d) => v.push((d as u8).into()),
+                        None => {
+                            return Err(
+                                ParseError::in_line(i + 1, &line, field, "not a digit").into()
+                            );
+                        }
                     }
                 }
             }
@@ -78,15 +198,259 @@ where
 
     Ok(numbers)
 }
+
+/// get_input_lines reads all lines of the input, without line endings (`\n` or `\r\n`)
+/// or trailing whitespace.
 pub fn get_input_lines(f: impl std::io::Read) -> Result<Vec<String>> {
     let buf = std::io::BufReader::new(f);
 
     let v = buf
         .lines()
+        .map(|line| line.map(|line| line.trim_end().to_owned()))
         .collect::<std::result::Result<Vec<String>, std::io::Error>>()?;
     Ok(v)
 }
 
+/// get_numbered_lines reads all lines of the input like `get_input_lines`, keeping their
+/// line numbers for error messages.
+pub fn get_numbered_lines(f: impl std::io::Read) -> Result<Vec<NumberedLine>> {
+    let lines = get_input_lines(f)?
+        .into_iter()
+        .enumerate()
+        .map(|(i, text)| NumberedLine {
+            number: i + 1,
+            text,
+        })
+        .collect();
+    Ok(lines)
+}
+
+/// get_input_line reads an input that is a single line, ignoring blank lines around it.
+pub fn get_input_line(f: impl std::io::Read) -> Result<NumberedLine> {
+    let mut lines = get_numbered_lines(f)?.into_iter().filter(|l| !l.is_blank());
+    let line = lines
+        .next()
+        .ok_or_else(|| ParseError::new(1, 1, "", "empty input"))?;
+    if let Some(extra) = lines.next() {
+        return Err(extra.error(extra.field(), "expected a single line").into());
+    }
+    Ok(line)
+}
+
+/// get_input_number_list parses an input that is a single line of numbers separated by
+/// `delimiter`, such as `3,4,3,1,2`.
+pub fn get_input_number_list<T>(f: impl std::io::Read, delimiter: &str) -> Result<Vec<T>>
+where
+    T: FromStr,
+    T::Err: Display,
+{
+    let line = get_input_line(f)?;
+    Ok(line.numbers(line.field(), delimiter)?)
+}
+
+/// get_input_sections reads the input as sections of lines separated by one or more
+/// blank lines. Blank lines at the start and end of the input are ignored.
+pub fn get_input_sections(f: impl std::io::Read) -> Result<Vec<Vec<NumberedLine>>> {
+    let mut sections = vec![];
+    let mut section = vec![];
+    for line in get_numbered_lines(f)? {
+        if !line.is_blank() {
+            section.push(line);
+        } else if !section.is_empty() {
+            sections.push(std::mem::take(&mut section));
+        }
+    }
+    if !section.is_empty() {
+        sections.push(section);
+    }
+    Ok(sections)
+}
+
+/// Field is a part of a line of input and its byte offset in the line, so that errors
+/// point at the right column. Its methods mirror those of `str`, keeping track of the
+/// offset.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Field<'a> {
+    pub text: &'a str,
+    /// offset is the byte offset of `text` in its line.
+    pub offset: usize,
+}
+
+impl<'a> Field<'a> {
+    /// new returns a field of a whole line.
+    pub fn new(line: &'a str) -> Self {
+        Self {
+            text: line,
+            offset: 0,
+        }
+    }
+
+    /// slice returns the part of the field in a byte range of its text.
+    pub fn slice(&self, range: std::ops::Range<usize>) -> Self {
+        Self {
+            text: &self.text[range.clone()],
+            offset: self.offset + range.start,
+        }
+    }
+
+    pub fn trim(&self) -> Self {
+        let start = self.text.len() - self.text.trim_start().len();
+        let end = start + self.text[start..].trim_end().len();
+        self.slice(start..end)
+    }
+
+    /// split_once splits the field around the first `delimiter`.
+    pub fn split_once(&self, delimiter: &str) -> Option<(Self, Self)> {
+        let i = self.text.find(delimiter)?;
+        let rest = self.slice(i + delimiter.len()..self.text.len());
+        Some((self.slice(0..i), rest))
+    }
+
+    pub fn strip_prefix(&self, prefix: &str) -> Option<Self> {
+        let rest = self.text.strip_prefix(prefix)?;
+        Some(self.slice(prefix.len()..prefix.len() + rest.len()))
+    }
+
+    pub fn strip_suffix(&self, suffix: &str) -> Option<Self> {
+        let rest = self.text.strip_suffix(suffix)?;
+        Some(self.slice(0..rest.len()))
+    }
+
+    /// split splits the field on `delimiter` and trims the items. A blank delimiter splits
+    /// on any run of whitespace.
+    pub fn split(&self, delimiter: &str) -> impl Iterator<Item = Field<'a>> {
+        let mut fields = vec![];
+        if delimiter.trim().is_empty() {
+            let mut start = None;
+            for (i, c) in self.text.char_indices() {
+                match (c.is_whitespace(), start) {
+                    (true, Some(s)) => {
+                        fields.push(self.slice(s..i));
+                        start = None;
+                    }
+                    (false, None) => start = Some(i),
+                    _ => {}
+                }
+            }
+            if let Some(s) = start {
+                fields.push(self.slice(s..self.text.len()));
+            }
+        } else {
+            let mut rest = *self;
+            while let Some((field, after)) = rest.split_once(delimiter) {
+                fields.push(field.trim());
+                rest = after;
+            }
+            fields.push(rest.trim());
+        }
+        fields.into_iter()
+    }
+
+    /// chars returns every character of the field with the field of just that character.
+    pub fn chars(&self) -> impl Iterator<Item = (char, Field<'a>)> + 'a {
+        let field = *self;
+        self.text
+            .char_indices()
+            .map(move |(i, c)| (c, field.slice(i..i + c.len_utf8())))
+    }
+}
+
+/// NumberedLine is a line of input and its 1-based line number.
+///
+/// The parsing helpers take a Field of the line, so that errors point at the right
+/// column.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NumberedLine {
+    pub number: usize,
+    pub text: String,
+}
+
+impl NumberedLine {
+    pub fn as_str(&self) -> &str {
+        &self.text
+    }
+
+    pub fn is_blank(&self) -> bool {
+        self.text.trim().is_empty()
+    }
+
+    /// field returns the whole line as a field.
+    pub fn field(&self) -> Field<'_> {
+        Field::new(&self.text)
+    }
+
+    /// error creates a ParseError for `field` in this line.
+    pub fn error(&self, field: Field, reason: impl Display) -> ParseError {
+        ParseError::in_line(self.number, &self.text, field, reason)
+    }
+
+    /// parse parses `field`, ignoring surrounding whitespace.
+    pub fn parse<T>(&self, field: Field) -> Result<T, ParseError>
+    where
+        T: FromStr,
+        T::Err: Display,
+    {
+        parse_in_line(self.number, &self.text, field.trim())
+    }
+
+    /// numbers parses a list of numbers in `field` separated by `delimiter`. A blank
+    /// delimiter splits on any run of whitespace.
+    pub fn numbers<T>(&self, field: Field, delimiter: &str) -> Result<Vec<T>, ParseError>
+    where
+        T: FromStr,
+        T::Err: Display,
+    {
+        field.split(delimiter).map(|n| self.parse(n)).collect()
+    }
+
+    /// rule splits an `a -> b` rule into its trimmed sides.
+    pub fn rule(&self) -> Result<(Field<'_>, Field<'_>), ParseError> {
+        let (a, b) = self
+            .field()
+            .split_once("->")
+            .ok_or_else(|| self.error(self.field(), "expected 'a -> b'"))?;
+        Ok((a.trim(), b.trim()))
+    }
+
+    /// key_range parses `key=start..end` in `field` into the key and the inclusive range.
+    pub fn key_range<'a, T>(
+        &self,
+        field: Field<'a>,
+    ) -> Result<(Field<'a>, RangeInclusive<T>), ParseError>
+    where
+        T: FromStr,
+        T::Err: Display,
+    {
+        let (key, range) = field
+            .split_once("=")
+            .ok_or_else(|| self.error(field, "expected 'key=start..end'"))?;
+        let (start, end) = range
+            .split_once("..")
+            .ok_or_else(|| self.error(range, "expected a range 'start..end'"))?;
+        Ok((key.trim(), self.parse(start)?..=self.parse(end)?))
+    }
+
+    /// key_ranges parses a comma separated list of `key=start..end` in `field`.
+    pub fn key_ranges<'a, T>(
+        &self,
+        field: Field<'a>,
+    ) -> Result<Vec<(Field<'a>, RangeInclusive<T>)>, ParseError>
+    where
+        T: FromStr,
+        T::Err: Display,
+    {
+        let mut rest = field;
+        let mut ranges = vec![];
+        while let Some((part, after)) = rest.split_once(",") {
+            ranges.push(self.key_range(part)?);
+            rest = after;
+        }
+        ranges.push(self.key_range(rest)?);
+        Ok(ranges)
+    }
+}
+
+/// lines_as_char_columns transposes lines into columns of characters.
 pub fn lines_as_char_columns(lines: Vec<String>) -> impl Iterator<Item = Vec<char>> {
     let rows = lines
         .into_iter()
@@ -145,3 +509,78 @@ fn test_get_input_columns() {
 
     assert!(iter.into_iter().eq(v.into_iter()));
 }
+
+#[test]
+fn test_parse_error() {
+    let line = "fold along x=1O";
+    let err: Result<i16, _> = parse_in_line(3, line, Field::new(line).slice(13..15));
+    let err = err.unwrap_err();
+    assert_eq!((err.line, err.column, err.text.as_str()), (3, 14, "1O"));
+    assert_eq!(
+        err.with_day(13).to_string(),
+        "day 13, line 3, column 14: invalid digit found in string: '1O'"
+    );
+
+    let err = get_input_number_grid::<u8>("123\n1x3\n".as_bytes()).unwrap_err();
+    let err = err.downcast::<ParseError>().unwrap();
+    assert_eq!((err.line, err.column, err.text.as_str()), (2, 2, "x"));
+
+    let err = get_input_number_grid::<u8>("123\n12\n".as_bytes()).unwrap_err();
+    assert_eq!(err.downcast::<ParseError>().unwrap().line, 2);
+
+    let err = get_input_hex("D2FE2".as_bytes()).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "line 1, column 5: odd number of hex digits: '2'"
+    );
+    let err = get_input_hex("D2FÉ28".as_bytes()).unwrap_err();
+    assert_eq!(err.to_string(), "line 1, column 4: bad hex: 'É'");
+    // Columns and lines count from the start of the input, not the first digit.
+    let err = get_input_hex("\n  D2FE2\n".as_bytes()).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "line 2, column 7: odd number of hex digits: '2'"
+    );
+    let err = get_input_hex(" \tD2xE".as_bytes()).unwrap_err();
+    assert_eq!(err.to_string(), "line 1, column 5: bad hex: 'x'");
+}
+
+#[test]
+fn test_input_combinators() {
+    let s = "\r\n3,4, 3\r\n\r\n\r\nCH -> B \r\nx=20..30, y=-10..-5\n";
+    let sections = get_input_sections(s.as_bytes()).unwrap();
+    assert_eq!(sections.len(), 2);
+    assert_eq!(sections[0][0].number, 2);
+    assert_eq!(
+        sections[0][0]
+            .numbers::<u8>(sections[0][0].field(), ",")
+            .unwrap(),
+        [3, 4, 3]
+    );
+
+    let (rule, range) = (&sections[1][0], &sections[1][1]);
+    assert_eq!(rule.text, "CH -> B");
+    let (a, b) = rule.rule().unwrap();
+    assert_eq!((a.text, b.text), ("CH", "B"));
+    assert_eq!((a.offset, b.offset), (0, 6));
+    let ranges = range.key_ranges(range.field()).unwrap();
+    let ranges = ranges
+        .into_iter()
+        .map(|(key, range)| (key.text, range))
+        .collect::<Vec<_>>();
+    assert_eq!(ranges, vec![("x", 20..=30), ("y", -10..=-5)]);
+    let err = range
+        .key_range::<u8>(range.field().slice(10..range.text.len()))
+        .unwrap_err();
+    assert_eq!((err.line, err.column, err.text.as_str()), (6, 13, "-10"));
+    let numbers = Field::new(" 1  2\t3 ");
+    let fields = numbers.split(" ").collect::<Vec<_>>();
+    assert_eq!(
+        fields
+            .iter()
+            .map(|f| (f.text, f.offset))
+            .collect::<Vec<_>>(),
+        [("1", 1), ("2", 4), ("3", 6)]
+    );
+    assert_eq!(range.numbers::<u8>(numbers, " ").unwrap(), [1, 2, 3]);
+}
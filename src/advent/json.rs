@@ -0,0 +1,29 @@
+//! Minimal helpers for writing JSON output.
+
+use std::fmt::Write;
+
+/// string returns `s` as a quoted and escaped JSON string.
+pub fn string(s: &str) -> String {
+    let mut out = String::with_capacity(s.len() + 2);
+    out.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if (c as u32) < 0x20 => write!(out, "\\u{:04x}", c as u32).unwrap(),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+#[test]
+fn test_string() {
+    assert_eq!(string("abc"), r#""abc""#);
+    assert_eq!(string("a\"b\\c\n"), r#""a\"b\\c\n""#);
+    assert_eq!(string("\u{1}"), r#""\u0001""#);
+}
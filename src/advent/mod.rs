@@ -1,3 +1,10 @@
+use std::any::Any;
+use std::fmt::Display;
+use std::io::Read;
+use std::panic::{self, AssertUnwindSafe};
+
+use anyhow::Result;
+
 pub mod day1;
 pub mod day10;
 pub mod day11;
@@ -18,4 +25,157 @@ pub mod day7;
 pub mod day8;
 pub mod day9;
 
+pub mod answers;
+pub mod bench;
+pub mod geometry;
+pub mod grid;
 pub mod input;
+pub(crate) mod json;
+pub mod runner;
+
+/// Solution is implemented by every day's puzzle solver.
+///
+/// Parsing is kept separate from solving so that the parsed input can be shared
+/// between the two parts (and timed on its own).
+pub trait Solution {
+    /// DAY is the day of the advent calendar the solution is for.
+    const DAY: u8;
+    /// TITLE is the name of the day's puzzle.
+    const TITLE: &'static str;
+
+    /// Input is the parsed puzzle input.
+    type Input: 'static;
+
+    fn parse(&self, input: impl Read) -> Result<Self::Input>;
+    fn part1(&self, input: &Self::Input) -> Result<Answer>;
+    fn part2(&self, input: &Self::Input) -> Result<Answer>;
+}
+
+/// Answer is the result of solving one part of a puzzle.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Answer {
+    Number(i128),
+    Text(String),
+}
+
+impl Display for Answer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Answer::Number(n) => write!(f, "{}", n),
+            Answer::Text(s) => f.write_str(s),
+        }
+    }
+}
+
+macro_rules! answer_from_number {
+    ($($t:ty),*) => {
+        $(
+            impl From<$t> for Answer {
+                fn from(n: $t) -> Self {
+                    Answer::Number(n as i128)
+                }
+            }
+        )*
+    };
+}
+
+answer_from_number!(u8, u16, u32, u64, usize, i8, i16, i32, i64, isize);
+
+impl From<String> for Answer {
+    fn from(s: String) -> Self {
+        Answer::Text(s)
+    }
+}
+
+/// DynSolution is the object safe version of `Solution`, used by the registry.
+///
+/// The parsed input is passed around as `dyn Any` and must have been produced by
+/// the same solution's `parse`. A `ParseError` returned by `parse` has its day set, and a
+/// panic while parsing or solving is returned as an error so one bad day doesn't stop the
+/// others.
+pub trait DynSolution: Sync {
+    fn day(&self) -> u8;
+    fn title(&self) -> &'static str;
+    fn parse(&self, input: &mut dyn Read) -> Result<Box<dyn Any>>;
+    fn part1(&self, input: &dyn Any) -> Result<Answer>;
+    fn part2(&self, input: &dyn Any) -> Result<Answer>;
+}
+
+impl<S> DynSolution for S
+where
+    S: Solution + Sync,
+{
+    fn day(&self) -> u8 {
+        S::DAY
+    }
+
+    fn title(&self) -> &'static str {
+        S::TITLE
+    }
+
+    fn parse(&self, input: &mut dyn Read) -> Result<Box<dyn Any>> {
+        match catch_panic(|| Solution::parse(self, input)) {
+            Ok(parsed) => Ok(Box::new(parsed)),
+            Err(err) => match err.downcast::<input::ParseError>() {
+                Ok(err) => Err(err.with_day(S::DAY).into()),
+                Err(err) => Err(err),
+            },
+        }
+    }
+
+    fn part1(&self, input: &dyn Any) -> Result<Answer> {
+        catch_panic(|| Solution::part1(self, downcast_input::<S>(input)?))
+    }
+
+    fn part2(&self, input: &dyn Any) -> Result<Answer> {
+        catch_panic(|| Solution::part2(self, downcast_input::<S>(input)?))
+    }
+}
+
+/// catch_panic calls `f`, returning a panic as an error with the panic message.
+fn catch_panic<T>(f: impl FnOnce() -> Result<T>) -> Result<T> {
+    panic::catch_unwind(AssertUnwindSafe(f)).unwrap_or_else(|payload| {
+        let msg = match payload.downcast_ref::<&str>() {
+            Some(msg) => msg.to_string(),
+            None => match payload.downcast_ref::<String>() {
+                Some(msg) => msg.clone(),
+                None => "unknown panic".to_owned(),
+            },
+        };
+        Err(anyhow::format_err!("panicked: {}", msg))
+    })
+}
+
+fn downcast_input<S: Solution>(input: &dyn Any) -> Result<&S::Input> {
+    input
+        .downcast_ref::<S::Input>()
+        .ok_or_else(|| anyhow::format_err!("input was not parsed by day {}", S::DAY))
+}
+
+/// SOLUTIONS lists every solved day, ordered by day.
+pub static SOLUTIONS: [&dyn DynSolution; 19] = [
+    &day1::Solver,
+    &day2::Solver,
+    &day3::Solver,
+    &day4::Solver,
+    &day5::Solver,
+    &day6::Solver,
+    &day7::Solver,
+    &day8::Solver,
+    &day9::Solver,
+    &day10::Solver,
+    &day11::Solver,
+    &day12::Solver,
+    &day13::Solver,
+    &day14::Solver,
+    &day15::Solver,
+    &day16::Solver,
+    &day17::Solver,
+    &day18::Solver,
+    &day19::Solver,
+];
+
+/// solution returns the solution registered for `day`, if any.
+pub fn solution(day: u8) -> Option<&'static dyn DynSolution> {
+    SOLUTIONS.iter().copied().find(|s| s.day() == day)
+}
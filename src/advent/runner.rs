@@ -0,0 +1,237 @@
+use std::fmt::Display;
+use std::io::Read;
+use std::path::PathBuf;
+use std::str::FromStr;
+use std::time::{Duration, Instant};
+
+use anyhow::Result;
+
+use super::{input::read_file, json, solution, Answer, DynSolution};
+
+/// Part selects which half of a day's puzzle to solve.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Part {
+    One,
+    Two,
+}
+
+impl Part {
+    pub const ALL: [Part; 2] = [Part::One, Part::Two];
+
+    pub fn number(&self) -> u8 {
+        match self {
+            Part::One => 1,
+            Part::Two => 2,
+        }
+    }
+}
+
+impl Display for Part {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.number())
+    }
+}
+
+impl FromStr for Part {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "1" => Ok(Part::One),
+            "2" => Ok(Part::Two),
+            _ => Err(anyhow::format_err!("bad part '{}', expected 1 or 2", s)),
+        }
+    }
+}
+
+/// Input is where a day's puzzle input is read from.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Input {
+    Stdin,
+    File(PathBuf),
+}
+
+impl Input {
+    /// default_for returns the conventional input location for a day, `./data/day<N>.txt`.
+    pub fn default_for(day: u8) -> Self {
+        Input::File(PathBuf::from(format!("./data/day{}.txt", day)))
+    }
+
+    pub fn open(&self) -> Result<Box<dyn Read>> {
+        match self {
+            Input::Stdin => Ok(Box::new(std::io::stdin())),
+            Input::File(path) => {
+                let f = read_file(path.clone()).map_err(|err| {
+                    err.context(format!("failed to open input {}", path.display()))
+                })?;
+                Ok(Box::new(f))
+            }
+        }
+    }
+}
+
+/// Outcome is the result of running one part of a day.
+#[derive(Debug)]
+pub struct Outcome {
+    pub day: u8,
+    pub part: Part,
+    pub answer: Result<Answer>,
+    /// duration is the time spent solving the part, not including parsing.
+    pub duration: Duration,
+}
+
+impl Outcome {
+    /// failed creates an outcome for a part that could not be run.
+    pub fn failed(day: u8, part: Part, err: anyhow::Error) -> Self {
+        Self {
+            day,
+            part,
+            answer: Err(err),
+            duration: Duration::ZERO,
+        }
+    }
+
+    /// to_json returns the outcome as a JSON object with the fields `day`, `part`,
+    /// `answer`, `duration_ms` and `error`. Either `answer` or `error` is null.
+    pub fn to_json(&self) -> String {
+        let (answer, error) = match &self.answer {
+            Ok(Answer::Number(n)) => (n.to_string(), "null".to_owned()),
+            Ok(Answer::Text(s)) => (json::string(s), "null".to_owned()),
+            Err(err) => ("null".to_owned(), json::string(&format!("{:#}", err))),
+        };
+        format!(
+            r#"{{"day":{},"part":{},"answer":{},"duration_ms":{},"error":{}}}"#,
+            self.day,
+            self.part,
+            answer,
+            self.duration.as_micros() as f64 / 1000.0,
+            error
+        )
+    }
+}
+
+/// run parses the input and solves the requested parts of the day.
+///
+/// If the input can't be parsed every requested part fails with the parse error.
+pub fn run(solution: &dyn DynSolution, input: &mut dyn Read, parts: &[Part]) -> Vec<Outcome> {
+    let day = solution.day();
+    let parsed = match solution.parse(input) {
+        Ok(parsed) => parsed,
+        Err(err) => {
+            let err = err.context("failed to parse input");
+            return parts
+                .iter()
+                .map(|&part| Outcome::failed(day, part, anyhow::format_err!("{:#}", err)))
+                .collect();
+        }
+    };
+
+    parts
+        .iter()
+        .map(|&part| {
+            let start = Instant::now();
+            let answer = match part {
+                Part::One => solution.part1(parsed.as_ref()),
+                Part::Two => solution.part2(parsed.as_ref()),
+            };
+            Outcome {
+                day,
+                part,
+                answer,
+                duration: start.elapsed(),
+            }
+        })
+        .collect()
+}
+
+/// run_day runs the requested parts of a registered day, reading the input from `input`
+/// or the day's default input.
+///
+/// A day that isn't registered, or whose input can't be read, fails every part.
+pub fn run_day(day: u8, input: Option<&Input>, parts: &[Part]) -> Vec<Outcome> {
+    let fail = |err: anyhow::Error| -> Vec<Outcome> {
+        parts
+            .iter()
+            .map(|&part| Outcome::failed(day, part, anyhow::format_err!("{:#}", err)))
+            .collect()
+    };
+
+    let solution = match solution(day) {
+        Some(solution) => solution,
+        None => return fail(anyhow::format_err!("day {} is not solved", day)),
+    };
+
+    let mut reader = match input
+        .cloned()
+        .unwrap_or_else(|| Input::default_for(day))
+        .open()
+    {
+        Ok(reader) => reader,
+        Err(err) => return fail(err),
+    };
+
+    run(solution, &mut reader, parts)
+}
+
+#[test]
+fn test_outcome_to_json() {
+    let outcome = Outcome {
+        day: 13,
+        part: Part::Two,
+        answer: Ok(Answer::Text("a\nb".to_owned())),
+        duration: Duration::from_micros(1500),
+    };
+    assert_eq!(
+        outcome.to_json(),
+        r#"{"day":13,"part":2,"answer":"a\nb","duration_ms":1.5,"error":null}"#
+    );
+
+    let outcome = Outcome::failed(1, Part::One, anyhow::Error::msg("bad input"));
+    assert_eq!(
+        outcome.to_json(),
+        r#"{"day":1,"part":1,"answer":null,"duration_ms":0,"error":"bad input"}"#
+    );
+}
+
+#[test]
+fn test_run_failures() {
+    struct Panicking;
+
+    impl super::Solution for Panicking {
+        const DAY: u8 = 99;
+        const TITLE: &'static str = "Panicking";
+
+        type Input = ();
+
+        fn parse(&self, _input: impl Read) -> Result<Self::Input> {
+            Ok(())
+        }
+
+        fn part1(&self, _input: &Self::Input) -> Result<Answer> {
+            panic!("no answer")
+        }
+
+        fn part2(&self, _input: &Self::Input) -> Result<Answer> {
+            Ok(2.into())
+        }
+    }
+
+    // A panic fails only the part it happened in.
+    let outcomes = run(&Panicking, &mut &b""[..], &Part::ALL);
+    assert_eq!(
+        outcomes[0].answer.as_ref().unwrap_err().to_string(),
+        "panicked: no answer"
+    );
+    assert_eq!(outcomes[1].answer.as_ref().unwrap(), &Answer::Number(2));
+
+    let day7 = solution(7).unwrap();
+    let outcomes = run(day7, &mut &b"5\n"[..], &Part::ALL);
+    assert_eq!(outcomes[0].answer.as_ref().unwrap(), &Answer::Number(0));
+
+    let day12 = solution(12).unwrap();
+    let outcomes = run(day12, &mut &b"a-b\n"[..], &[Part::One]);
+    assert_eq!(
+        outcomes[0].answer.as_ref().unwrap_err().to_string(),
+        "no start cave"
+    );
+}
@@ -0,0 +1,250 @@
+use std::path::PathBuf;
+
+use anyhow::Result;
+
+use advent2021::runner::{Input, Part};
+
+pub const USAGE: &str = "Usage:
+    main list
+    main run (--all | --day <N>...) [--part <1|2>] [--input <PATH|->] [--format <text|json>]
+    main verify [--answers <PATH>] [--day <N>...]
+    main bench [--day <N>...] [--input <PATH|->] [--runs <N>] [--csv <PATH>]
+
+Commands:
+    list    List the solved days
+    run     Solve days, reading input from ./data/day<N>.txt by default
+    verify  Check answers against the expected ones in an answers file
+    bench   Time parsing and solving of days, every solved day by default
+
+Options:
+    --all           Run every solved day
+    --day <N>       Run day N, may be repeated
+    --part <1|2>    Only run the given part
+    --input <PATH>  Read the input from PATH, or from stdin if PATH is '-'.
+                    Requires a single --day
+    --format <FMT>  Output format of run, text or json [default: text]
+    --answers <PATH>
+                    Answers file to verify against [default: answers.toml]
+    --runs <N>      Number of times to run every phase [default: 10]
+    --csv <PATH>    Also write the benchmark results to PATH as CSV";
+
+/// Command is a parsed command line.
+#[derive(Debug, PartialEq)]
+pub enum Command {
+    List,
+    Run(RunArgs),
+    Verify(VerifyArgs),
+    Bench(BenchArgs),
+    Help,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct RunArgs {
+    /// days to run, `None` means every solved day.
+    pub days: Option<Vec<u8>>,
+    pub parts: Vec<Part>,
+    pub input: Option<Input>,
+    pub format: Format,
+}
+
+/// Format is the output format of the run command.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Format {
+    Text,
+    /// Json outputs a JSON array with one object per part run.
+    Json,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct VerifyArgs {
+    pub answers: PathBuf,
+    /// days to verify, `None` means every solved day.
+    pub days: Option<Vec<u8>>,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct BenchArgs {
+    /// days to benchmark, `None` means every solved day.
+    pub days: Option<Vec<u8>>,
+    pub input: Option<Input>,
+    pub runs: usize,
+    pub csv: Option<PathBuf>,
+}
+
+pub fn parse_args(args: impl IntoIterator<Item = String>) -> Result<Command> {
+    let mut args = args.into_iter();
+    let command = match args.next() {
+        Some(command) => command,
+        None => return Ok(Command::Help),
+    };
+
+    match command.as_str() {
+        "list" => {
+            if let Some(arg) = args.next() {
+                return Err(anyhow::format_err!("unexpected argument '{}'", arg));
+            }
+            Ok(Command::List)
+        }
+        "run" => parse_run_args(args).map(Command::Run),
+        "verify" => parse_verify_args(args).map(Command::Verify),
+        "bench" => parse_bench_args(args).map(Command::Bench),
+        "help" | "-h" | "--help" => Ok(Command::Help),
+        _ => Err(anyhow::format_err!("unknown command '{}'", command)),
+    }
+}
+
+fn parse_run_args(mut args: impl Iterator<Item = String>) -> Result<RunArgs> {
+    let mut all = false;
+    let mut days = vec![];
+    let mut parts = Part::ALL.to_vec();
+    let mut input = None;
+    let mut format = Format::Text;
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--all" => all = true,
+            "--day" => days.push(parse_day(&flag_value(&mut args, &arg)?)?),
+            "--part" => parts = vec![flag_value(&mut args, &arg)?.parse()?],
+            "--input" => input = Some(parse_input(&flag_value(&mut args, &arg)?)),
+            "--format" => {
+                format = match flag_value(&mut args, &arg)?.as_str() {
+                    "text" => Format::Text,
+                    "json" => Format::Json,
+                    f => return Err(anyhow::format_err!("unknown format '{}'", f)),
+                }
+            }
+            _ => return Err(anyhow::format_err!("unexpected argument '{}'", arg)),
+        }
+    }
+
+    if all != days.is_empty() {
+        return Err(anyhow::Error::msg("run requires either --all or --day"));
+    }
+    if input.is_some() && days.len() != 1 {
+        return Err(anyhow::Error::msg("--input requires a single --day"));
+    }
+
+    Ok(RunArgs {
+        days: if all { None } else { Some(days) },
+        parts,
+        input,
+        format,
+    })
+}
+
+fn parse_verify_args(mut args: impl Iterator<Item = String>) -> Result<VerifyArgs> {
+    let mut answers = PathBuf::from("answers.toml");
+    let mut days = vec![];
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--answers" => answers = PathBuf::from(flag_value(&mut args, &arg)?),
+            "--day" => days.push(parse_day(&flag_value(&mut args, &arg)?)?),
+            _ => return Err(anyhow::format_err!("unexpected argument '{}'", arg)),
+        }
+    }
+
+    Ok(VerifyArgs {
+        answers,
+        days: if days.is_empty() { None } else { Some(days) },
+    })
+}
+
+fn parse_bench_args(mut args: impl Iterator<Item = String>) -> Result<BenchArgs> {
+    let mut days = vec![];
+    let mut input = None;
+    let mut runs = 10;
+    let mut csv = None;
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--day" => days.push(parse_day(&flag_value(&mut args, &arg)?)?),
+            "--input" => input = Some(parse_input(&flag_value(&mut args, &arg)?)),
+            "--runs" => {
+                let n = flag_value(&mut args, &arg)?;
+                runs = match n.parse() {
+                    Ok(n) if n > 0 => n,
+                    _ => return Err(anyhow::format_err!("bad number of runs '{}'", n)),
+                };
+            }
+            "--csv" => csv = Some(PathBuf::from(flag_value(&mut args, &arg)?)),
+            _ => return Err(anyhow::format_err!("unexpected argument '{}'", arg)),
+        }
+    }
+
+    if input.is_some() && days.len() != 1 {
+        return Err(anyhow::Error::msg("--input requires a single --day"));
+    }
+
+    Ok(BenchArgs {
+        days: if days.is_empty() { None } else { Some(days) },
+        input,
+        runs,
+        csv,
+    })
+}
+
+fn parse_input(s: &str) -> Input {
+    match s {
+        "-" => Input::Stdin,
+        path => Input::File(PathBuf::from(path)),
+    }
+}
+
+fn parse_day(s: &str) -> Result<u8> {
+    s.parse()
+        .map_err(|_| anyhow::format_err!("bad day '{}'", s))
+}
+
+fn flag_value(args: &mut impl Iterator<Item = String>, flag: &str) -> Result<String> {
+    args.next()
+        .ok_or_else(|| anyhow::format_err!("{} requires a value", flag))
+}
+
+#[test]
+fn test_parse_args() {
+    let args = |s: &str| parse_args(s.split_whitespace().map(String::from));
+
+    assert_eq!(args("list").unwrap(), Command::List);
+    assert_eq!(args("").unwrap(), Command::Help);
+    assert_eq!(
+        args("run --day 15 --part 2 --input -").unwrap(),
+        Command::Run(RunArgs {
+            days: Some(vec![15]),
+            parts: vec![Part::Two],
+            input: Some(Input::Stdin),
+            format: Format::Text,
+        })
+    );
+    assert_eq!(
+        args("run --all --format json").unwrap(),
+        Command::Run(RunArgs {
+            days: None,
+            parts: vec![Part::One, Part::Two],
+            input: None,
+            format: Format::Json,
+        })
+    );
+    assert_eq!(
+        args("verify --day 3").unwrap(),
+        Command::Verify(VerifyArgs {
+            answers: PathBuf::from("answers.toml"),
+            days: Some(vec![3]),
+        })
+    );
+    assert_eq!(
+        args("bench --day 19 --runs 3 --csv out.csv").unwrap(),
+        Command::Bench(BenchArgs {
+            days: Some(vec![19]),
+            input: None,
+            runs: 3,
+            csv: Some(PathBuf::from("out.csv")),
+        })
+    );
+    assert!(args("bench --runs 0").is_err());
+    assert!(args("run").is_err());
+    assert!(args("run --all --day 1").is_err());
+    assert!(args("run --day 1 --day 2 --input x.txt").is_err());
+    assert!(args("run --day 1 --part 3").is_err());
+    assert!(args("run --day 1 --format xml").is_err());
+}
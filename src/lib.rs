@@ -0,0 +1,9 @@
+//! Solutions to [Advent of Code 2021](https://adventofcode.com/2021/).
+//!
+//! Every day has a module with its parser and solvers, and a `Solver` implementing
+//! [`Solution`]. The [`SOLUTIONS`] registry lists all of them, and the [`runner`],
+//! [`answers`] and [`bench`](mod@bench) modules run, verify and time them.
+
+mod advent;
+
+pub use advent::*;
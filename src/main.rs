@@ -1,30 +1,194 @@
-#![allow(dead_code)]
-
 use anyhow::Result;
 
-mod advent;
-use advent::{input::read_file, *};
-
-fn main() -> Result<()> {
-    day1::print_depth_increases(read_file("./data/day1.txt")?)?;
-    day2::print_position(read_file("./data/day2.txt")?)?;
-    day3::print_power_consumption_and_life_support_rating(read_file("./data/day3.txt")?)?;
-    day4::print_result(read_file("./data/day4.txt")?)?;
-    day5::print_overlapping_lines(read_file("./data/day5.txt")?)?;
-    day6::print_fish_count_after_80_days(read_file("./data/day6.txt")?)?;
-    day7::print_optimal_position_and_fuel_requirement(read_file("./data/day7.txt")?)?;
-    day8::print_result(read_file("./data/day8.txt")?)?;
-    day9::print_result(read_file("./data/day9.txt")?)?;
-    day10::print_result(read_file("./data/day10.txt")?)?;
-    day11::print_result(read_file("./data/day11.txt")?)?;
-    day12::print_result(read_file("./data/day12.txt")?)?;
-    day13::print_result(read_file("./data/day13.txt")?)?;
-    day14::print_result(read_file("./data/day14.txt")?)?;
-    day15::print_result(read_file("./data/day15.txt")?)?;
-    day16::print_result(read_file("./data/day16.txt")?)?;
-    day17::print_result(read_file("./data/day17.txt")?)?;
-    day18::print_result(read_file("./data/day18.txt")?)?;
-    day19::print_result(read_file("./data/day19.txt")?)?;
-
-    Ok(())
+mod cli;
+
+use advent2021::{
+    answers::{self, Verdict},
+    bench::{self, Timing},
+    input::read_file,
+    runner::{run_day, Input, Outcome, Part},
+    SOLUTIONS,
+};
+use cli::{BenchArgs, Command, Format, RunArgs, VerifyArgs};
+
+fn main() {
+    let command = match cli::parse_args(std::env::args().skip(1)) {
+        Ok(command) => command,
+        Err(err) => {
+            eprintln!("error: {}\n\n{}", err, cli::USAGE);
+            std::process::exit(2);
+        }
+    };
+
+    let ok = match command {
+        Command::Help => {
+            println!("{}", cli::USAGE);
+            true
+        }
+        Command::List => {
+            for solution in SOLUTIONS.iter() {
+                println!("{:>2}  {}", solution.day(), solution.title());
+            }
+            true
+        }
+        Command::Run(args) => run(args),
+        Command::Verify(args) => match verify(args) {
+            Ok(ok) => ok,
+            Err(err) => {
+                eprintln!("error: {:#}", err);
+                false
+            }
+        },
+        Command::Bench(args) => match run_bench(args) {
+            Ok(ok) => ok,
+            Err(err) => {
+                eprintln!("error: {:#}", err);
+                false
+            }
+        },
+    };
+
+    if !ok {
+        std::process::exit(1);
+    }
+}
+
+/// run solves the selected days and prints the answers. A day that fails doesn't stop
+/// the remaining days from running.
+///
+/// Returns false if any part failed.
+fn run(args: RunArgs) -> bool {
+    let days = args
+        .days
+        .unwrap_or_else(|| SOLUTIONS.iter().map(|s| s.day()).collect());
+
+    let mut ok = true;
+    let mut records = vec![];
+    for day in days {
+        let outcomes = run_day(day, args.input.as_ref(), &args.parts);
+        for outcome in outcomes.iter() {
+            match args.format {
+                Format::Text => print_outcome(outcome),
+                Format::Json => records.push(outcome.to_json()),
+            }
+            ok &= outcome.answer.is_ok();
+        }
+    }
+    if args.format == Format::Json {
+        println!("[\n  {}\n]", records.join(",\n  "));
+    }
+    ok
+}
+
+/// verify runs the parts that have expected answers and reports whether they pass.
+/// Parts of the selected days without an expected answer are reported as missing.
+///
+/// Returns false if any part failed.
+fn verify(args: VerifyArgs) -> Result<bool> {
+    let expected = answers::read_answers(read_file(args.answers.clone()).map_err(|err| {
+        err.context(format!("failed to open answers {}", args.answers.display()))
+    })?)?;
+    let days = args
+        .days
+        .unwrap_or_else(|| SOLUTIONS.iter().map(|s| s.day()).collect());
+
+    let (mut passed, mut failed, mut missing) = (0, 0, 0);
+    for &day in days.iter() {
+        for part in Part::ALL {
+            let label = format!("day {} part {}", day, part);
+            let mut entries = expected
+                .iter()
+                .filter(|e| e.day == day && e.part == part)
+                .peekable();
+            if entries.peek().is_none() {
+                println!("MISSING {}", label);
+                missing += 1;
+                continue;
+            }
+
+            for entry in entries {
+                let input = Input::File(entry.input.clone());
+                let outcome = run_day(day, Some(&input), &[part]).remove(0);
+                match answers::check(entry, &outcome) {
+                    Verdict::Pass => {
+                        println!("PASS    {} ({})", label, entry.input.display());
+                        passed += 1;
+                    }
+                    Verdict::Fail(reason) => {
+                        println!("FAIL    {} ({}): {}", label, entry.input.display(), reason);
+                        failed += 1;
+                    }
+                }
+            }
+        }
+    }
+    println!(
+        "\n{} passed, {} failed, {} missing",
+        passed, failed, missing
+    );
+
+    Ok(failed == 0)
+}
+
+/// run_bench times the selected days and prints a table of the results, optionally
+/// writing them to a CSV file as well.
+///
+/// Returns false if any phase failed.
+fn run_bench(args: BenchArgs) -> Result<bool> {
+    let days = args
+        .days
+        .unwrap_or_else(|| SOLUTIONS.iter().map(|s| s.day()).collect());
+
+    println!(
+        "{:>3}  {:<6} {:>5} {:>12} {:>12} {:>12}",
+        "day", "phase", "runs", "min", "median", "max"
+    );
+    let mut timings: Vec<Timing> = vec![];
+    for day in days {
+        let day_timings = match bench::bench_day(day, args.input.as_ref(), args.runs) {
+            Ok(timings) => timings,
+            Err(err) => {
+                println!("{:>3}  error: {:#}", day, err);
+                timings.push(Timing {
+                    day,
+                    phase: bench::Phase::Parse,
+                    stats: Err(err),
+                });
+                continue;
+            }
+        };
+        for t in day_timings.iter() {
+            match &t.stats {
+                Ok(s) => println!(
+                    "{:>3}  {:<6} {:>5} {:>12.2?} {:>12.2?} {:>12.2?}",
+                    t.day, t.phase, s.runs, s.min, s.median, s.max
+                ),
+                Err(err) => println!("{:>3}  {:<6} error: {:#}", t.day, t.phase, err),
+            }
+        }
+        timings.extend(day_timings);
+    }
+
+    if let Some(path) = args.csv {
+        let f = std::fs::File::create(&path)
+            .map_err(|err| anyhow::format_err!("failed to create {}: {}", path.display(), err))?;
+        bench::write_csv(std::io::BufWriter::new(f), &timings)?;
+    }
+
+    Ok(timings.iter().all(|t| t.stats.is_ok()))
+}
+
+fn print_outcome(outcome: &Outcome) {
+    let label = format!("Day {} part {}", outcome.day, outcome.part);
+    match &outcome.answer {
+        Ok(answer) => {
+            let answer = answer.to_string();
+            if answer.contains('\n') {
+                println!("{}: ({:.2?})\n{}", label, outcome.duration, answer);
+            } else {
+                println!("{}: {} ({:.2?})", label, answer, outcome.duration);
+            }
+        }
+        Err(err) => println!("{}: error: {:#}", label, err),
+    }
 }